@@ -6,4 +6,4 @@
 #SBATCH --error=system_err
 #SBATCH --partition=kmo
 
-cargo run --release
+cargo run --release -- batch jobs/szymczak-wide-full.jobs
@@ -1,7 +1,12 @@
-from launcher import launch
+import subprocess
+
 from plotter import plot
 
 max_dim = 2
+# finished jobs are skipped, so an interrupted sweep can be simply rerun
+subprocess.run(
+    ["cargo", "run", "--release", "--", "batch", "jobs/szymczak-wide-full.jobs"],
+    check=False,
+)
 for base in range(2, 63):
-    launch(base, max_dim)
     plot(base, max_dim, "szymczak-wide", full=True)
@@ -0,0 +1,603 @@
+use crate::{
+    category::{
+        morphism::{Endo as EndoMorphism, IsBij, IsMap, IsMatching, IsWide, Morphism},
+        object::Object,
+        HomSets, PrettyName,
+    },
+    util::json::{Json, ToJson},
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::{Arc, OnceLock},
+};
+
+/* # arena */
+
+/**
+objects and morphisms numbered densely, the morphisms of every hom-set being consecutive.
+*/
+struct Arena<O, M> {
+    objects: Vec<O>,
+    morphisms: Vec<M>,
+    // the source and the target of every morphism
+    ends: Vec<(usize, usize)>,
+    hom_sets: Vec<Vec<Range<usize>>>,
+    identities: Vec<usize>,
+    ids: HashMap<M, usize>,
+    compositions: Option<CompositionTables>,
+}
+
+/**
+the table for the objects a, b and c holds the composites of hom(a, b) x hom(b, c),
+the composites of the first morphism of hom(a, b) coming first.
+it is filled in on the first composition of a pair from these hom-sets.
+*/
+type CompositionTables = Vec<Vec<Vec<OnceLock<Box<[usize]>>>>>;
+
+impl<O, M: Hash + Eq> Arena<O, M> {
+    fn object(&self, id: usize) -> &O {
+        self.objects.get(id).expect("object should be in the arena")
+    }
+
+    fn morphism(&self, id: usize) -> &M {
+        self.morphisms
+            .get(id)
+            .expect("morphism should be in the arena")
+    }
+
+    fn ends(&self, id: usize) -> (usize, usize) {
+        *self.ends.get(id).expect("morphism should be in the arena")
+    }
+
+    fn hom(&self, source: usize, target: usize) -> Range<usize> {
+        self.hom_sets
+            .get(source)
+            .and_then(|hom_sets_fixed_source| hom_sets_fixed_source.get(target))
+            .expect("source and target should be objects in the arena")
+            .clone()
+    }
+
+    fn identity(&self, object: usize) -> usize {
+        *self
+            .identities
+            .get(object)
+            .expect("object should be in the arena")
+    }
+
+    fn id(&self, morphism: &M) -> usize {
+        *self
+            .ids
+            .get(morphism)
+            .expect("the category should be closed under composition")
+    }
+
+    fn compose<Q: Object>(&self, left: usize, right: usize) -> usize
+    where
+        M: Morphism<Q>,
+    {
+        let compose_morphisms = |first: usize, second: usize| {
+            self.id(&self.morphism(first).compose(self.morphism(second)))
+        };
+        let Some(ref compositions) = self.compositions else {
+            return compose_morphisms(left, right);
+        };
+
+        let (source, middle) = self.ends(left);
+        let (right_source, target) = self.ends(right);
+        debug_assert_eq!(middle, right_source, "morphisms should be composable");
+        let left_hom = self.hom(source, middle);
+        let right_hom = self.hom(middle, target);
+        // the table is filled sequentially, since blocking in another thread of the pool
+        // could wait for the very same table
+        let table = compositions
+            .get(source)
+            .and_then(|tables| tables.get(middle))
+            .and_then(|tables| tables.get(target))
+            .expect("source and target should be objects in the arena")
+            .get_or_init(|| {
+                left_hom
+                    .clone()
+                    .flat_map(|first| right_hom.clone().map(move |second| (first, second)))
+                    .map(|(first, second)| compose_morphisms(first, second))
+                    .collect()
+            });
+        *table
+            .get(
+                left.wrapping_sub(left_hom.start)
+                    .wrapping_mul(right_hom.len())
+                    .wrapping_add(right.wrapping_sub(right_hom.start)),
+            )
+            .expect("morphisms should be in their hom-sets")
+    }
+
+    fn nof_tabulated_compositions(&self) -> usize {
+        self.compositions.as_ref().map_or(0, |compositions| {
+            compositions
+                .iter()
+                .flatten()
+                .flatten()
+                .filter_map(OnceLock::get)
+                .map(|table| table.len())
+                .sum()
+        })
+    }
+}
+
+/* # handles */
+
+/**
+an object of an arena category. handles are compared by their ids only,
+so those from different arenas should not be mixed.
+*/
+pub struct ArenaObject<O, M> {
+    id: usize,
+    arena: Arc<Arena<O, M>>,
+}
+
+/**
+a morphism of an arena category, compared by its id only, as the objects are
+*/
+pub struct ArenaMorphism<O, M> {
+    id: usize,
+    arena: Arc<Arena<O, M>>,
+}
+
+impl<O, M: Hash + Eq> ArenaObject<O, M> {
+    pub const fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn get(&self) -> &O {
+        self.arena.object(self.id)
+    }
+}
+
+impl<O, M: Hash + Eq> ArenaMorphism<O, M> {
+    pub const fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn get(&self) -> &M {
+        self.arena.morphism(self.id)
+    }
+}
+
+/* ## basic traits are implemented by hand, in order to compare the ids only */
+
+impl<O, M> Clone for ArenaObject<O, M> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            arena: Arc::clone(&self.arena),
+        }
+    }
+}
+
+impl<O, M> PartialEq for ArenaObject<O, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<O, M> Eq for ArenaObject<O, M> {}
+
+impl<O, M> Hash for ArenaObject<O, M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<O, M> Clone for ArenaMorphism<O, M> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            arena: Arc::clone(&self.arena),
+        }
+    }
+}
+
+impl<O, M> PartialEq for ArenaMorphism<O, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<O, M> Eq for ArenaMorphism<O, M> {}
+
+impl<O, M> Hash for ArenaMorphism<O, M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/* ## the rest is delegated to the objects and morphisms in the arena */
+
+impl<O: fmt::Debug, M: Hash + Eq> fmt::Debug for ArenaObject<O, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<O: fmt::Display, M: Hash + Eq> fmt::Display for ArenaObject<O, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<O: ToJson, M: Hash + Eq> ToJson for ArenaObject<O, M> {
+    fn to_json(&self) -> Json {
+        self.get().to_json()
+    }
+}
+
+impl<O: PrettyName, M> PrettyName for ArenaObject<O, M> {
+    const PRETTY_NAME: &'static str = O::PRETTY_NAME;
+}
+
+impl<O, M: Hash + Eq + fmt::Debug> fmt::Debug for ArenaMorphism<O, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<O, M: Hash + Eq + fmt::Display> fmt::Display for ArenaMorphism<O, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<O, M: Hash + Eq + ToJson> ToJson for ArenaMorphism<O, M> {
+    fn to_json(&self) -> Json {
+        self.get().to_json()
+    }
+}
+
+impl<O, M: PrettyName> PrettyName for ArenaMorphism<O, M> {
+    const PRETTY_NAME: &'static str = M::PRETTY_NAME;
+}
+
+/* ## category structure */
+
+impl<O: Object, M> Object for ArenaObject<O, M> {}
+
+impl<O: Object, M: Morphism<O> + Hash + Eq> Morphism<ArenaObject<O, M>> for ArenaMorphism<O, M> {
+    type B = ArenaObject<O, M>;
+
+    fn source(&self) -> Self::B {
+        ArenaObject {
+            id: self.arena.ends(self.id).0,
+            arena: Arc::clone(&self.arena),
+        }
+    }
+
+    fn target(&self) -> Self::B {
+        ArenaObject {
+            id: self.arena.ends(self.id).1,
+            arena: Arc::clone(&self.arena),
+        }
+    }
+
+    fn compose(&self, other: &Self) -> Self {
+        Self {
+            id: self.arena.compose(self.id, other.id),
+            arena: Arc::clone(&self.arena),
+        }
+    }
+}
+
+impl<O: Object, M: Morphism<O> + Hash + Eq> EndoMorphism<ArenaObject<O, M>>
+    for ArenaMorphism<O, M>
+{
+    fn identity(object: Self::B) -> Self {
+        Self {
+            id: object.arena.identity(object.id),
+            arena: object.arena,
+        }
+    }
+}
+
+impl<O: Object, M: IsMap<O> + Hash + Eq> IsMap<ArenaObject<O, M>> for ArenaMorphism<O, M> {
+    fn is_a_map(&self) -> bool {
+        self.get().is_a_map()
+    }
+}
+
+impl<O: Object, M: IsMatching<O> + Hash + Eq> IsMatching<ArenaObject<O, M>>
+    for ArenaMorphism<O, M>
+{
+    fn is_a_matching(&self) -> bool {
+        self.get().is_a_matching()
+    }
+}
+
+impl<O: Object, M: IsWide<O> + Hash + Eq> IsWide<ArenaObject<O, M>> for ArenaMorphism<O, M> {
+    fn is_wide(&self) -> bool {
+        self.get().is_wide()
+    }
+}
+
+impl<O: Object, M: IsBij<O> + Hash + Eq> IsBij<ArenaObject<O, M>> for ArenaMorphism<O, M> {
+    fn is_a_bijection(&self) -> bool {
+        self.get().is_a_bijection()
+    }
+}
+
+/* # arena category */
+
+/**
+a category whose objects and morphisms are handles into an arena.
+the morphisms are generated once, by the category the arena is built from,
+and afterwards the hom-sets are ranges of ids and equality is a comparison of the ids.
+*/
+pub struct ArenaCategory<O, M> {
+    arena: Arc<Arena<O, M>>,
+    objects: Vec<ArenaObject<O, M>>,
+    // a handle of every morphism, so the hom-sets are borrowed from it
+    morphisms: Vec<ArenaMorphism<O, M>>,
+}
+
+impl<O: Object + Hash + Clone, M: EndoMorphism<O>> ArenaCategory<O, M> {
+    /**
+    the same category, composing the morphisms themselves and looking the composite up by its hash.
+    this is slower than the category it is built from, as the whole morphism is hashed
+    after every composition, so it is meant as the reference for the tables
+    */
+    pub fn from_hom_sets<C: HomSets<O, M>>(category: &C) -> Self {
+        Self::build(category, false)
+    }
+
+    /**
+    the same category, with the composition of the morphisms looked up in tables
+    instead of being computed every time.
+    every table takes the product of the sizes of its two hom-sets,
+    so this pays off when the same morphisms are composed over and over again,
+    as in the functors
+    */
+    pub fn with_composition_tables<C: HomSets<O, M>>(category: &C) -> Self {
+        Self::build(category, true)
+    }
+
+    fn build<C: HomSets<O, M>>(category: &C, composition_tables: bool) -> Self {
+        let objects: Vec<O> = category.objects().cloned().collect();
+        let mut morphisms: Vec<M> = Vec::new();
+        let mut ends = Vec::new();
+        let hom_sets: Vec<Vec<Range<usize>>> = objects
+            .iter()
+            .enumerate()
+            .map(|(source_id, source)| {
+                objects
+                    .iter()
+                    .enumerate()
+                    .map(|(target_id, target)| {
+                        let start = morphisms.len();
+                        morphisms.extend(category.hom(source, target).iter().cloned());
+                        ends.resize(morphisms.len(), (source_id, target_id));
+                        start..morphisms.len()
+                    })
+                    .collect()
+            })
+            .collect();
+        let ids: HashMap<M, usize> = morphisms.iter().cloned().zip(0..).collect();
+
+        // every hom-set of endomorphisms contains the identity, so it is not empty
+        let identities = hom_sets
+            .iter()
+            .enumerate()
+            .map(|(object, hom_sets_fixed_source)| {
+                let any_endo = hom_sets_fixed_source
+                    .get(object)
+                    .and_then(|endos| morphisms.get(endos.start))
+                    .expect("there is an identity on every object");
+                *ids.get(&M::identity(any_endo.source()))
+                    .expect("the identity should be in the category")
+            })
+            .collect();
+        let compositions = composition_tables.then(|| {
+            objects
+                .iter()
+                .map(|_| {
+                    objects
+                        .iter()
+                        .map(|_| objects.iter().map(|_| OnceLock::new()).collect())
+                        .collect()
+                })
+                .collect()
+        });
+
+        let arena = Arc::new(Arena {
+            objects,
+            morphisms,
+            ends,
+            hom_sets,
+            identities,
+            ids,
+            compositions,
+        });
+        Self {
+            objects: (0..arena.objects.len())
+                .map(|id| ArenaObject {
+                    id,
+                    arena: Arc::clone(&arena),
+                })
+                .collect(),
+            morphisms: (0..arena.morphisms.len())
+                .map(|id| ArenaMorphism {
+                    id,
+                    arena: Arc::clone(&arena),
+                })
+                .collect(),
+            arena,
+        }
+    }
+}
+
+impl<O, M> ArenaCategory<O, M> {
+    pub fn nof_objects(&self) -> usize {
+        self.arena.objects.len()
+    }
+
+    pub fn nof_morphisms(&self) -> usize {
+        self.arena.morphisms.len()
+    }
+
+    /**
+    the number of the entries in the tables filled in so far
+    */
+    pub fn nof_tabulated_compositions(&self) -> usize
+    where
+        M: Hash + Eq,
+    {
+        self.arena.nof_tabulated_compositions()
+    }
+}
+
+impl<O: Object + Send + Sync, M: Morphism<O> + Hash + Eq + Send + Sync>
+    HomSets<ArenaObject<O, M>, ArenaMorphism<O, M>> for ArenaCategory<O, M>
+{
+    type Morphisms<'hom>
+        = &'hom [ArenaMorphism<O, M>]
+    where
+        Self: 'hom;
+
+    fn objects<'objects>(&'objects self) -> impl Iterator<Item = &'objects ArenaObject<O, M>>
+    where
+        ArenaObject<O, M>: 'objects,
+    {
+        self.objects.iter()
+    }
+
+    fn hom(&self, source: &ArenaObject<O, M>, target: &ArenaObject<O, M>) -> Self::Morphisms<'_> {
+        self.morphisms
+            .get(self.arena.hom(source.id, target.id))
+            .expect("source and target should be objects in the arena")
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        category::{
+            functors::szymczak::SzymczakClasses,
+            object::Concrete,
+            relation::{CanonModule, Relation},
+            Category,
+        },
+        ralg::cgroup::{ideal::CIdeal, C},
+    };
+    use typenum::U4 as N;
+
+    type R = C<N>;
+    type I = CIdeal<N>;
+    type Zn = CanonModule<R, I>;
+
+    #[test]
+    fn arena_is_the_same_category() {
+        let category = Category::<Zn, Relation<R, I>>::new(1);
+        let arena = ArenaCategory::from_hom_sets(&category);
+        assert_eq!(arena.nof_objects(), 3);
+        assert_eq!(
+            arena.nof_morphisms(),
+            category.clone().into_morphisms().len()
+        );
+
+        for source in arena.objects() {
+            for target in arena.objects() {
+                let hom = arena.hom(source, target);
+                assert!(hom
+                    .iter()
+                    .map(ArenaMorphism::get)
+                    .eq(category.hom(source.get(), target.get()).iter()));
+
+                for morphism in hom {
+                    assert_eq!(
+                        ArenaMorphism::identity(morphism.source()).compose(morphism),
+                        *morphism
+                    );
+                    for other in arena.hom(target, source) {
+                        assert_eq!(
+                            morphism.compose(other).get(),
+                            &morphism.get().compose(other.get())
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn composing_with_tables() {
+        let category = Category::<Zn, Relation<R, I>>::new(1);
+        let arena = ArenaCategory::from_hom_sets(&category);
+        let tabulated = ArenaCategory::with_composition_tables(&category);
+        assert_eq!(
+            tabulated.nof_tabulated_compositions(),
+            0,
+            "tables are filled lazily"
+        );
+
+        let z2 = tabulated
+            .objects()
+            .find(|object| object.get().cardinality() == 2)
+            .expect("there is a module of cardinality 2");
+        let z4 = tabulated
+            .objects()
+            .find(|object| object.get().cardinality() == 4)
+            .expect("there is a module of cardinality 4");
+        for left in tabulated.hom(z2, z4) {
+            for right in tabulated.hom(z4, z2) {
+                assert_eq!(left.compose(right).get(), &left.get().compose(right.get()));
+            }
+        }
+        assert_eq!(
+            tabulated.nof_tabulated_compositions(),
+            tabulated.hom(z2, z4).len() * tabulated.hom(z4, z2).len(),
+            "only the table of hom(Z2, Z4) x hom(Z4, Z2) is filled"
+        );
+
+        let classes = SzymczakClasses::functor(&tabulated, 2);
+        assert_eq!(
+            classes.number_of_endomorphisms(),
+            SzymczakClasses::functor(&arena, 2).number_of_endomorphisms()
+        );
+    }
+
+    #[test]
+    fn szymczak_classes_in_an_arena() {
+        let category = Category::<Zn, Relation<R, I>>::new(1);
+        let arena = ArenaCategory::from_hom_sets(&category);
+
+        let class_sizes = |sizes: Vec<usize>| {
+            let mut sorted = sizes;
+            sorted.sort_unstable();
+            sorted
+        };
+        let expected = SzymczakClasses::functor(&category, 2);
+        let classes = SzymczakClasses::functor(&arena, 2);
+        assert_eq!(
+            class_sizes(
+                classes
+                    .buffer
+                    .iter()
+                    .map(|class| class.values().map(Vec::len).sum())
+                    .collect()
+            ),
+            class_sizes(
+                expected
+                    .buffer
+                    .iter()
+                    .map(|class| class.values().map(Vec::len).sum())
+                    .collect()
+            )
+        );
+        assert_eq!(
+            classes.number_of_endomorphisms(),
+            expected.number_of_endomorphisms()
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    category::{functors::Functor, relation::Relation},
+    ralg::{
+        module::{canon::object::Object as CanonModule, map::CanonToCanon},
+        ring::{
+            ideal::{Ideal, Principal as PrincipalIdeal},
+            Ring,
+        },
+    },
+};
+use std::marker::PhantomData;
+
+/**
+the embedding of modules and their homomorphisms into modules and relations,
+sending every map to its graph
+*/
+pub struct Graph<R: Ring, I: Ideal<Parent = R> + Ord> {
+    ring: PhantomData<(R, I)>,
+}
+
+impl<R: Ring, I: Ideal<Parent = R> + Ord> Default for Graph<R, I> {
+    fn default() -> Self {
+        Self { ring: PhantomData }
+    }
+}
+
+impl<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    Functor<CanonModule<R, I>, CanonToCanon<R, I>, CanonModule<R, I>, Relation<R, I>>
+    for Graph<R, I>
+{
+    fn map_object(&self, object: &CanonModule<R, I>) -> CanonModule<R, I> {
+        object.clone()
+    }
+
+    fn map_morphism(&self, morphism: &CanonToCanon<R, I>) -> Relation<R, I> {
+        Relation::graph(morphism)
+    }
+}
+
+impl<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord> Graph<R, I> {
+    /**
+    the functor is faithful and its image consists of the relations which are maps,
+    so it can be inverted on them
+    */
+    #[allow(clippy::unused_self, reason = "the functor carries no data")]
+    pub fn try_preimage(&self, relation: &Relation<R, I>) -> Option<CanonToCanon<R, I>> {
+        relation.try_into_map()
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        category::{
+            laws::verify_functor,
+            morphism::{Enumerable, IsMap, Morphism},
+            object::PartiallyEnumerable,
+            Category,
+        },
+        ralg::cgroup::{ideal::CIdeal, C},
+    };
+    use std::sync::Arc;
+    use typenum::U4 as N;
+
+    type R = C<N>;
+    type I = CIdeal<N>;
+    type Zn = CanonModule<R, I>;
+
+    #[test]
+    fn graph_is_a_functor() {
+        let objects: Vec<Arc<Zn>> = Zn::all_by_dimension(0..=1).map(Arc::new).collect();
+        let identities: Vec<CanonToCanon<R, I>> =
+            objects.iter().map(CanonToCanon::identity).collect();
+        let maps: Vec<CanonToCanon<R, I>> = objects
+            .iter()
+            .flat_map(|source| {
+                objects
+                    .iter()
+                    .flat_map(|target| CanonToCanon::hom(Arc::clone(source), Arc::clone(target)))
+            })
+            .collect();
+
+        let report = verify_functor(&Graph::default(), &identities, &maps);
+        assert!(report.holds(), "{report}");
+    }
+
+    #[test]
+    fn graphs_are_the_relations_which_are_maps() {
+        let graph = Graph::<R, I>::default();
+        let category = Category::<Zn, Relation<R, I>>::new(1);
+
+        for relation in category.into_morphisms() {
+            let preimage = graph.try_preimage(&relation);
+            assert_eq!(preimage.is_some(), relation.is_a_map());
+            if let Some(map) = preimage {
+                assert_eq!(graph.map_morphism(&map), relation);
+                assert_eq!(map.source(), relation.source());
+                assert_eq!(map.target(), relation.target());
+            }
+        }
+    }
+}
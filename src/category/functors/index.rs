@@ -0,0 +1,245 @@
+use crate::category::{
+    functors::{IsoClasses, Wrapper},
+    morphism::{Endo as Morphism, Persistent as PersistentMorphism},
+    object::Object,
+    HomSets,
+};
+use crate::util::checkpoint::Persistent;
+use rayon::prelude::*;
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt::{self, Display},
+    fs,
+    hash::Hash,
+    io,
+    marker::PhantomData,
+    path::Path,
+    sync::Arc,
+};
+
+/**
+position of a class in the buffer of the classes. the functor puts them in order
+by `IsoClasses::order_classes`, so it is the same in every run for the morphisms which can be stored
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ClassId(pub usize);
+
+impl Display for ClassId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/**
+one endomorphism of every class, which is enough to tell the class of any other one.
+the category it is used with has to contain the objects of the representatives
+as well as the ones of the endomorphisms to be classified
+*/
+pub struct ClassIndex<O: Object + Hash, M: Morphism<O>, W: Wrapper<O, M>> {
+    // the name of the filter of the classified endomorphisms
+    pub filter: String,
+    representatives: Vec<W>,
+    buckets: HashMap<W::Invariant, Vec<usize>>,
+    types: PhantomData<(O, M)>,
+}
+
+impl<
+        O: Object + Hash + Sync + Send,
+        M: Morphism<O> + Sync + Send,
+        W: Wrapper<O, M> + Sync + Send,
+    > ClassIndex<O, M, W>
+{
+    pub fn new(iso_classes: &IsoClasses<O, M, W>) -> Self {
+        Self::from_representatives(
+            iso_classes.filter.to_owned(),
+            iso_classes
+                .buffer
+                .iter()
+                .map(|iso_class| {
+                    let endo = iso_class
+                        .values()
+                        .flatten()
+                        .next()
+                        .expect("IsoClass is never empty");
+                    W::from_morphism(endo.clone()).expect("This morphism should be an endomorphism")
+                })
+                .collect(),
+        )
+    }
+
+    fn from_representatives(filter: String, representatives: Vec<W>) -> Self {
+        let mut buckets = HashMap::<W::Invariant, Vec<usize>>::new();
+        for (position, representative) in representatives.iter().enumerate() {
+            buckets
+                .entry(representative.invariant())
+                .or_default()
+                .push(position);
+        }
+        Self {
+            filter,
+            representatives,
+            buckets,
+            types: PhantomData,
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.representatives.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.representatives.is_empty()
+    }
+
+    /**
+    `None` if the morphism is not an endomorphism or if it is not isomorphic to any of the classes.
+    the filter is not applied, so an endomorphism left out of the classes
+    may still be isomorphic to one of them
+    */
+    pub fn classify<C: HomSets<O, M>>(&self, endo: &M, category: &C) -> Option<ClassId> {
+        let endo_wrapped = W::from_morphism(endo.clone())?;
+        self.buckets
+            .get(&endo_wrapped.invariant())?
+            .par_iter()
+            .copied()
+            .find_any(|&position| {
+                W::are_isomorphic(
+                    &endo_wrapped,
+                    self.representatives
+                        .get(position)
+                        .expect("the bucket holds positions of the representatives"),
+                    category,
+                )
+            })
+            .map(ClassId)
+    }
+}
+
+/**
+the bytes an endomorphism is stored as, unique within the category
+*/
+fn encoding<O: Object + Persistent, M: Morphism<O> + PersistentMorphism<O>>(endo: &M) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    endo.source().borrow().persist(&mut bytes);
+    endo.persist(&mut bytes);
+    bytes
+}
+
+/**
+the key the classes are sorted by in `IsoClasses::order_classes`, taken from one endomorphism at a time.
+only the morphisms which can be stored have one, their encoding
+*/
+pub(super) trait OrderKey<O: Object> {
+    fn order_key(&self) -> Option<Vec<u8>>;
+}
+
+impl<O: Object, M: Morphism<O>> OrderKey<O> for M {
+    default fn order_key(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+impl<O: Object + Persistent, M: Morphism<O> + PersistentMorphism<O>> OrderKey<O> for M {
+    fn order_key(&self) -> Option<Vec<u8>> {
+        Some(encoding(self))
+    }
+}
+
+const INDEX_MAGIC: &str = "szymczak-leray class index";
+const INDEX_VERSION: u16 = 1;
+
+fn corrupted(reason: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+/**
+the index is a file of its own, in the encoding of the checkpoints.
+the ring is written in front of it, since the endomorphisms cannot be read over another one
+*/
+impl<
+        O: Object + Hash + Sync + Send + Persistent,
+        M: Morphism<O, B = Arc<O>> + PersistentMorphism<O> + Sync + Send,
+        W: Wrapper<O, M> + Sync + Send,
+    > ClassIndex<O, M, W>
+{
+    /**
+    the file is written under a temporary name first, as the results are
+    */
+    pub fn save(&self, path: &Path, ring: &str) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        INDEX_MAGIC.to_owned().persist(&mut bytes);
+        INDEX_VERSION.persist(&mut bytes);
+        ring.to_owned().persist(&mut bytes);
+        self.filter.persist(&mut bytes);
+        self.representatives.len().persist(&mut bytes);
+        for representative in &self.representatives {
+            bytes.extend(encoding(representative.morphism()));
+        }
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".part");
+        fs::write(&partial, bytes)?;
+        fs::rename(partial, path)
+    }
+
+    pub fn load(path: &Path, ring: &str) -> io::Result<Self> {
+        let contents = fs::read(path)?;
+        let mut bytes = contents.as_slice();
+
+        if String::restore(&mut bytes).as_deref() != Some(INDEX_MAGIC)
+            || u16::restore(&mut bytes) != Some(INDEX_VERSION)
+        {
+            return Err(corrupted(format!(
+                "{} is not a class index of this version",
+                path.display()
+            )));
+        }
+        match String::restore(&mut bytes) {
+            Some(ref tag) if tag == ring => {}
+            Some(tag) => {
+                return Err(corrupted(format!(
+                    "{} is an index over {tag}, not over {ring}",
+                    path.display()
+                )))
+            }
+            None => return Err(corrupted(format!("{} is truncated", path.display()))),
+        }
+        match Self::restore(&mut bytes) {
+            Some(index) if bytes.is_empty() => Ok(index),
+            _ => Err(corrupted(format!("{} is corrupted", path.display()))),
+        }
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Self> {
+        let filter = String::restore(bytes)?;
+        let mut representatives = Vec::new();
+        for _ in 0..usize::restore(bytes)? {
+            let object = Arc::new(O::restore(bytes)?);
+            representatives.push(W::from_morphism(M::restore(
+                Arc::clone(&object),
+                object,
+                bytes,
+            )?)?);
+        }
+        Some(Self::from_representatives(filter, representatives))
+    }
+}
+
+impl<
+        O: Object + Hash + Sync + Send,
+        M: Morphism<O> + Sync + Send,
+        W: Wrapper<O, M> + Sync + Send,
+    > IsoClasses<O, M, W>
+{
+    /**
+    builds the index of the classes on every call,
+    so `ClassIndex` should be used to classify more than a few endomorphisms
+    */
+    pub fn classify<C: HomSets<O, M>>(&self, endo: &M, category: &C) -> Option<ClassId> {
+        ClassIndex::new(self).classify(endo, category)
+    }
+}
@@ -1,165 +1,365 @@
 use crate::category::{
-    morphism::{Endo as Morphism, IsBij, IsMap, IsMatching, IsWide}, //i leave to you implementation of try_cycle for arbitrary morphism, afterwards it will be removed. CanonToCanon should implement the Hash trait if we want to put it in the functor
+    functors::index::{ClassId, OrderKey},
+    morphism::{
+        Endo as Morphism, IsBij, IsMap, IsMatching, IsWide, Persistent as PersistentMorphism,
+    }, //i leave to you implementation of try_cycle for arbitrary morphism, afterwards it will be removed. CanonToCanon should implement the Hash trait if we want to put it in the functor
     // importowanie Endo as Morphism jest strasznie cursed
     object::Object,
-    Category,
+    HomSets,
     PrettyName,
 };
+use crate::util::{
+    checkpoint::{Checkpoint, Persistent},
+    json::{Json, ToJson},
+    union_find::UnionFind,
+};
 use rayon::prelude::*;
 use std::{
     borrow::Borrow,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{self, Debug, Display},
     hash::Hash,
+    io,
     marker::{PhantomData, Send, Sync},
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
 };
 
+pub mod graph;
+pub mod index;
 pub mod szymczak;
 
+/**
+a functor given by its action on the objects and on the morphisms,
+the laws it should satisfy are checked by `laws::verify_functor`
+*/
+pub trait Functor<SourceObject, SourceMorphism, TargetObject, TargetMorphism> {
+    fn map_object(&self, object: &SourceObject) -> TargetObject;
+    fn map_morphism(&self, morphism: &SourceMorphism) -> TargetMorphism;
+}
+
 //a trait to store the endomorphisms with additional structure used to determine if two endomorphisms are equivalent
 pub trait Wrapper<O: Object + Hash, M: Morphism<O>>: Sized {
+    /**
+    a cheap value shared by the isomorphic endomorphisms,
+    only the endomorphisms with the same one are compared, `()` if there is none
+    */
+    type Invariant: Eq + Hash + Send + Sync;
+
     fn from_morphism(morphism: M) -> Option<Self>;
     fn into_morphism(self) -> M;
+    fn morphism(&self) -> &M;
+    fn invariant(&self) -> Self::Invariant;
 
-    fn are_isomorphic(left: &Self, right: &Self, category: &Category<O, M>) -> bool;
+    fn are_isomorphic<C: HomSets<O, M>>(left: &Self, right: &Self, category: &C) -> bool;
 }
 
 type Endos<M> = Vec<M>;
 type RawIsoClass<W> = Vec<W>;
 type IsoClass<O, M> = HashMap<O, Vec<M>>;
+// called with every finished part of the recursion, see `Progress`
+type Finished<'hook, W> = dyn Fn(u64, &[RawIsoClass<W>]) + Sync + 'hook;
+
+/**
+the endomorphisms to be classified, named as in the output.
+the classes of any other endomorphisms are given by `IsoClasses::functor_by`
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EndoFilter {
+    All,
+    #[default]
+    Wide,
+    Maps,
+    Matchings,
+    Bijections,
+}
+
+impl EndoFilter {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Wide => "wide",
+            Self::Maps => "maps",
+            Self::Matchings => "matchings",
+            Self::Bijections => "bijections",
+        }
+    }
+
+    pub fn admits<O: Object, M: IsWide<O> + IsMap<O> + IsMatching<O> + IsBij<O>>(
+        self,
+        endo: &M,
+    ) -> bool {
+        match self {
+            Self::All => true,
+            Self::Wide => endo.is_wide(),
+            Self::Maps => endo.is_a_map(),
+            Self::Matchings => endo.is_a_matching(),
+            Self::Bijections => endo.is_a_bijection(),
+        }
+    }
+}
+
+impl FromStr for EndoFilter {
+    type Err = ();
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "all" => Ok(Self::All),
+            "wide" => Ok(Self::Wide),
+            "maps" => Ok(Self::Maps),
+            "matchings" => Ok(Self::Matchings),
+            "bijections" => Ok(Self::Bijections),
+            _ => Err(()),
+        }
+    }
+}
 
-#[derive(Debug)]
 pub struct IsoClasses<O: Object + Hash, M: Morphism<O>, W: Wrapper<O, M>> {
     pub buffer: Vec<IsoClass<O, M>>,
+    // the name of the filter of the endomorphisms
+    pub filter: &'static str,
     pub wrapper: PhantomData<W>,
 }
 
+// derived implementation would require the wrapper to be debuggable as well
+impl<O: Object + Hash + Debug, M: Morphism<O> + Debug, W: Wrapper<O, M>> Debug
+    for IsoClasses<O, M, W>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IsoClasses")
+            .field("buffer", &self.buffer)
+            .field("filter", &self.filter)
+            .finish()
+    }
+}
+
 impl<
         O: Object + Hash + Clone + Sync + Send,
-        M: Morphism<O> + Sync + Send + IsWide<O>,
+        M: Morphism<O> + Sync + Send + IsWide<O> + IsMap<O> + IsMatching<O> + IsBij<O>,
         W: Wrapper<O, M> + Sync + Send,
     > IsoClasses<O, M, W>
 {
-    pub fn functor<const RECURSION_PARAMETER: usize>(category: &Category<O, M>) -> Self {
+    /**
+    the classes of the wide endomorphisms
+    */
+    pub fn functor<C: HomSets<O, M>>(category: &C, recursion_parameter: usize) -> Self {
+        Self::functor_filtered(category, recursion_parameter, EndoFilter::default())
+    }
+
+    pub fn functor_filtered<C: HomSets<O, M>>(
+        category: &C,
+        recursion_parameter: usize,
+        filter: EndoFilter,
+    ) -> Self {
+        Self::functor_by(category, recursion_parameter, filter.name(), |endo| {
+            filter.admits(endo)
+        })
+    }
+
+    /**
+    the classes of the endomorphisms satisfying the predicate, which is recorded by its name
+    */
+    pub fn functor_by<C: HomSets<O, M>, P: Fn(&M) -> bool + Sync>(
+        category: &C,
+        recursion_parameter: usize,
+        filter: &'static str,
+        predicate: P,
+    ) -> Self {
         //step 0. If recursion parameter is less than 2, it will lead to the undefined behaviour
         assert!(
-            RECURSION_PARAMETER >= 2,
+            recursion_parameter >= 2,
             "parameter of recursion cannot be less that 2!"
         );
         //step 1. Clone all the endomorphisms (we will need them to be owned)
-
-        let endos: Endos<M> = category
-            .hom_sets
-            .par_iter()
-            .flat_map(|(source, hom_sets_fixed_source)| {
-                hom_sets_fixed_source
-                    .par_iter()
-                    .filter(move |(target, _)| *target == source)
-                    .flat_map(|(_, morphisms)| morphisms.par_iter().map(M::clone))
-            })
-            //temporary!!!!!
-            .filter(|morphism| morphism.is_wide())
-            .collect();
+        let endos = Self::endos(category, &predicate);
 
         //step 2. generate raw szymczak classes (by raw i mean they are unsorted by object and endomorphisms keep their cycles)
-        let raw_iso_classes = Self::raw_functor::<{ RECURSION_PARAMETER }>(endos, category);
+        let raw_iso_classes = Self::raw_functor(
+            endos,
+            category,
+            recursion_parameter,
+            1,
+            &|_part, _classes| {},
+        );
 
-        //step 3. clean up the szymczak classes
+        //step 3. clean up the szymczak classes and put them in order
         let buffer: Vec<IsoClass<O, M>> =
             raw_iso_classes.into_par_iter().map(Self::clean).collect();
 
-        Self {
+        let mut iso_classes = Self {
             buffer,
+            filter,
             wrapper: PhantomData::<W>,
-        }
+        };
+        iso_classes.order_classes();
+        iso_classes
     }
 
-    fn raw_functor<const RECURSION_PARAMETER: usize>(
+    fn endos<C: HomSets<O, M>, P: Fn(&M) -> bool + Sync>(category: &C, predicate: &P) -> Endos<M> {
+        category
+            .objects()
+            .collect::<Vec<&O>>()
+            .into_par_iter()
+            .flat_map_iter(|object| {
+                let morphisms = category.hom(object, object);
+                morphisms
+                    .iter()
+                    .filter(|morphism| predicate(morphism))
+                    .map(M::clone)
+                    .collect::<Vec<M>>()
+            })
+            .collect()
+    }
+
+    fn raw_functor<C: HomSets<O, M>>(
         mut endos: Endos<M>,
-        category: &Category<O, M>,
+        category: &C,
+        recursion_parameter: usize,
+        part: u64,
+        finished: &Finished<'_, W>,
     ) -> Vec<RawIsoClass<W>> {
-        if endos.len() > RECURSION_PARAMETER {
+        let raw_iso_classes = if endos.len() > recursion_parameter {
             let left_endos = endos.split_off(endos.len() / 2);
             let right_endos = endos;
             let (left_raw_iso_classes, right_raw_iso_classes) = rayon::join(
-                || Self::raw_functor::<{ RECURSION_PARAMETER }>(left_endos, category),
-                || Self::raw_functor::<{ RECURSION_PARAMETER }>(right_endos, category),
+                || {
+                    Self::raw_functor(
+                        left_endos,
+                        category,
+                        recursion_parameter,
+                        part.wrapping_mul(2),
+                        finished,
+                    )
+                },
+                || {
+                    Self::raw_functor(
+                        right_endos,
+                        category,
+                        recursion_parameter,
+                        part.wrapping_mul(2).wrapping_add(1),
+                        finished,
+                    )
+                },
             );
 
             Self::merge_raw_iso_classes(left_raw_iso_classes, right_raw_iso_classes, category)
         } else {
             Self::raw_functor_final_step(endos, category)
-        }
+        };
+        finished(part, &raw_iso_classes);
+        raw_iso_classes
     }
 
-    fn raw_functor_final_step(endos: Endos<M>, category: &Category<O, M>) -> Vec<RawIsoClass<W>> {
+    fn raw_functor_final_step<C: HomSets<O, M>>(
+        endos: Endos<M>,
+        category: &C,
+    ) -> Vec<RawIsoClass<W>> {
         let endos_wrapped = endos.into_iter().map(move |endo| {
             W::from_morphism(endo).expect("This morphism should be an endomorphism")
         });
 
-        endos_wrapped.fold(
-            Vec::<RawIsoClass<W>>::new(),
-            |mut raw_iso_classes, endo_wrapped /*Wrapper*/| {
-                let maybe_raw_iso_class: Option<&mut RawIsoClass<W>> =
-                    raw_iso_classes.par_iter_mut().find_any(|raw_iso_class| {
-                        W::are_isomorphic(
-                            &endo_wrapped,
-                            raw_iso_class.get(0).expect("RawIsoClass is never empty"),
-                            category,
-                        )
-                    });
-                if let Some(raw_iso_class) = maybe_raw_iso_class {
-                    raw_iso_class.push(endo_wrapped); //Wrapper
-                } else {
-                    let new_raw_iso_class: RawIsoClass<W> = vec![endo_wrapped]; //Wrapper
-                    raw_iso_classes.push(new_raw_iso_class);
-                }
-
+        let mut raw_iso_classes = Vec::<RawIsoClass<W>>::new();
+        // positions of the classes by the invariant of their members
+        let mut buckets = HashMap::<W::Invariant, Vec<usize>>::new();
+        for endo_wrapped in endos_wrapped {
+            let bucket = buckets.entry(endo_wrapped.invariant()).or_default();
+            let maybe_position = bucket.par_iter().copied().find_any(|&position| {
+                W::are_isomorphic(
+                    &endo_wrapped,
+                    Self::representative(&raw_iso_classes, position),
+                    category,
+                )
+            });
+            if let Some(position) = maybe_position {
                 raw_iso_classes
-            },
-        )
+                    .get_mut(position)
+                    .expect("the bucket holds positions of the classes")
+                    .push(endo_wrapped);
+            } else {
+                bucket.push(raw_iso_classes.len());
+                raw_iso_classes.push(vec![endo_wrapped]);
+            }
+        }
+        raw_iso_classes
     }
 
-    fn merge_raw_iso_classes(
-        mut left_raw_iso_classes: Vec<RawIsoClass<W>>,
-        mut right_raw_iso_classes: Vec<RawIsoClass<W>>,
-        category: &Category<O, M>,
-    ) -> Vec<RawIsoClass<W>> {
-        let mut merged_raw_iso_classes: Vec<RawIsoClass<W>> = left_raw_iso_classes.iter_mut().fold(
-            Vec::<RawIsoClass<W>>::new(),
-            |mut merged_raw_iso_classes, left_raw_iso_class| {
-                if let Some(right_raw_iso_class) =
-                    right_raw_iso_classes
-                        .par_iter_mut()
-                        .find_any(|right_raw_iso_class| {
-                            W::are_isomorphic(
-                                left_raw_iso_class
-                                    .get(0)
-                                    .expect("RawIsoClass is never empty"),
-                                right_raw_iso_class
-                                    .get(0)
-                                    .expect("RawIsoClass is never empty"),
-                                category,
-                            )
-                        })
-                {
-                    let mut merged_raw_iso_class = RawIsoClass::<W>::new();
-                    merged_raw_iso_class.append(left_raw_iso_class);
-                    merged_raw_iso_class.append(right_raw_iso_class);
-                    merged_raw_iso_classes.push(merged_raw_iso_class);
-                }
+    fn representative(raw_iso_classes: &[RawIsoClass<W>], position: usize) -> &W {
+        raw_iso_classes
+            .get(position)
+            .expect("the bucket holds positions of the classes")
+            .first()
+            .expect("RawIsoClass is never empty")
+    }
 
-                right_raw_iso_classes.retain(|right_raw_iso_class| !right_raw_iso_class.is_empty());
+    /**
+    every left class is compared with every right class of the same invariant,
+    which it is not yet joined with, and the isomorphic ones are joined.
+    so even if several classes of one side are isomorphic to a class of the other one,
+    all of them end up in a single class, whichever way the endomorphisms were split.
+    the classes are ordered by their first parts, the left ones coming before the right ones
+    */
+    fn merge_raw_iso_classes<C: HomSets<O, M>>(
+        left_raw_iso_classes: Vec<RawIsoClass<W>>,
+        right_raw_iso_classes: Vec<RawIsoClass<W>>,
+        category: &C,
+    ) -> Vec<RawIsoClass<W>> {
+        let nof_left = left_raw_iso_classes.len();
+        let parts: Vec<RawIsoClass<W>> = left_raw_iso_classes
+            .into_iter()
+            .chain(right_raw_iso_classes)
+            .collect();
 
-                merged_raw_iso_classes
-            },
-        );
-        left_raw_iso_classes.retain(|left_raw_szymczak_class| !left_raw_szymczak_class.is_empty());
+        // positions of the right classes by the invariant of their members
+        let mut buckets = HashMap::<W::Invariant, Vec<usize>>::new();
+        for position in nof_left..parts.len() {
+            buckets
+                .entry(Self::representative(&parts, position).invariant())
+                .or_default()
+                .push(position);
+        }
 
-        merged_raw_iso_classes.append(&mut left_raw_iso_classes);
-        merged_raw_iso_classes.append(&mut right_raw_iso_classes);
+        let mut joined = UnionFind::new(parts.len());
+        for left_position in 0..nof_left {
+            let left_representative = Self::representative(&parts, left_position);
+            let Some(bucket) = buckets.get(&left_representative.invariant()) else {
+                continue;
+            };
+            let candidates: Vec<usize> = bucket
+                .iter()
+                .copied()
+                .filter(|&right_position| !joined.are_joined(left_position, right_position))
+                .collect();
+            let isomorphic: Vec<usize> = candidates
+                .into_par_iter()
+                .filter(|&right_position| {
+                    W::are_isomorphic(
+                        left_representative,
+                        Self::representative(&parts, right_position),
+                        category,
+                    )
+                })
+                .collect();
+            for right_position in isomorphic {
+                joined.union(left_position, right_position);
+            }
+        }
 
-        merged_raw_iso_classes
+        let mut remaining_parts: Vec<Option<RawIsoClass<W>>> =
+            parts.into_iter().map(Some).collect();
+        joined
+            .into_sets()
+            .into_iter()
+            .map(|set| {
+                set.into_iter()
+                    .flat_map(|position| {
+                        remaining_parts
+                            .get_mut(position)
+                            .and_then(Option::take)
+                            .expect("every part is in exactly one set")
+                    })
+                    .collect()
+            })
+            .collect()
     }
 
     fn clean(raw_iso_class: RawIsoClass<W>) -> IsoClass<O, M> {
@@ -177,6 +377,191 @@ impl<
     }
 }
 
+/* ## checkpoints */
+
+type MorphismClasses<M> = Vec<Vec<M>>;
+
+/**
+finished parts of the recursion of the raw functor, kept for the checkpoint.
+the whole recursion is the part 1 and the halves of the part `k` are `2k` and `2k + 1`,
+so the halves can be forgotten once the part they make up is finished.
+the endomorphisms are kept without their wrappers, which are rebuilt when resuming.
+*/
+struct Progress<'checkpoint, O, M> {
+    checkpoint: &'checkpoint Checkpoint,
+    restored: Vec<MorphismClasses<M>>,
+    finished: Mutex<HashMap<u64, MorphismClasses<M>>>,
+    object_type: PhantomData<O>,
+}
+
+impl<
+        O: Object + Hash + Persistent,
+        M: Morphism<O, B = Arc<O>> + PersistentMorphism<O> + Sync + Send,
+    > Progress<'_, O, M>
+{
+    fn finish<W: Wrapper<O, M>>(&self, part: u64, raw_iso_classes: &[RawIsoClass<W>]) {
+        {
+            let mut finished = self.finished.lock().unwrap_or_else(PoisonError::into_inner);
+            finished.remove(&part.wrapping_mul(2));
+            finished.remove(&part.wrapping_mul(2).wrapping_add(1));
+            finished.insert(
+                part,
+                raw_iso_classes
+                    .iter()
+                    .map(|raw_iso_class| {
+                        raw_iso_class
+                            .iter()
+                            .map(|endo| endo.morphism().clone())
+                            .collect()
+                    })
+                    .collect(),
+            );
+        }
+        self.checkpoint.save_if_due(|bytes| {
+            let finished = self.finished.lock().unwrap_or_else(PoisonError::into_inner);
+            Self::persist(
+                &self
+                    .restored
+                    .iter()
+                    .chain(finished.values())
+                    .collect::<Vec<_>>(),
+                bytes,
+            );
+        });
+    }
+
+    fn persist(chunks: &[&MorphismClasses<M>], bytes: &mut Vec<u8>) {
+        chunks.len().persist(bytes);
+        for &classes in chunks {
+            classes.len().persist(bytes);
+            for class in classes {
+                class.len().persist(bytes);
+                for endo in class {
+                    endo.source().as_ref().persist(bytes);
+                    endo.persist(bytes);
+                }
+            }
+        }
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Vec<MorphismClasses<M>>> {
+        let mut chunks = Vec::new();
+        for _ in 0..usize::restore(bytes)? {
+            let mut classes = Vec::new();
+            for _ in 0..usize::restore(bytes)? {
+                let mut class = Vec::new();
+                for _ in 0..usize::restore(bytes)? {
+                    let object = Arc::new(O::restore(bytes)?);
+                    class.push(M::restore(Arc::clone(&object), object, bytes)?);
+                }
+                classes.push(class);
+            }
+            chunks.push(classes);
+        }
+        Some(chunks)
+    }
+}
+
+impl<
+        O: Object + Hash + Clone + Sync + Send + Persistent,
+        M: Morphism<O, B = Arc<O>>
+            + Sync
+            + Send
+            + IsWide<O>
+            + IsMap<O>
+            + IsMatching<O>
+            + IsBij<O>
+            + PersistentMorphism<O>,
+        W: Wrapper<O, M> + Sync + Send,
+    > IsoClasses<O, M, W>
+{
+    /**
+    the same classes as `functor_filtered`, with the finished parts of the recursion saved to the checkpoint.
+    when resuming, the endomorphisms found there are left out of the recursion
+    and their classes are merged with the new ones at the end.
+    the raw classes are saved once they are complete,
+    so a killed run does not have to find them again.
+    */
+    pub fn functor_checkpointed<C: HomSets<O, M>>(
+        category: &C,
+        recursion_parameter: usize,
+        filter: EndoFilter,
+        checkpoint: &Checkpoint,
+    ) -> io::Result<Self> {
+        assert!(
+            recursion_parameter >= 2,
+            "parameter of recursion cannot be less that 2!"
+        );
+        let restored = checkpoint
+            .load(Progress::<O, M>::restore)?
+            .unwrap_or_default();
+        let endos: Endos<M> = {
+            let done: HashSet<&M> = restored.iter().flatten().flatten().collect();
+            Self::endos(category, &|endo: &M| filter.admits(endo))
+                .into_iter()
+                .filter(|endo| !done.contains(endo))
+                .collect()
+        };
+
+        let progress = Progress {
+            checkpoint,
+            restored,
+            finished: Mutex::default(),
+            object_type: PhantomData::<O>,
+        };
+        let new_raw_iso_classes = Self::raw_functor(
+            endos,
+            category,
+            recursion_parameter,
+            1,
+            &|part, raw_iso_classes| progress.finish(part, raw_iso_classes),
+        );
+        let raw_iso_classes = progress.restored.into_iter().fold(
+            new_raw_iso_classes,
+            |merged_raw_iso_classes, classes| {
+                let restored_raw_iso_classes = classes
+                    .into_iter()
+                    .map(|class| {
+                        class
+                            .into_iter()
+                            .map(|endo| {
+                                W::from_morphism(endo)
+                                    .expect("This morphism should be an endomorphism")
+                            })
+                            .collect()
+                    })
+                    .collect();
+                Self::merge_raw_iso_classes(
+                    restored_raw_iso_classes,
+                    merged_raw_iso_classes,
+                    category,
+                )
+            },
+        );
+
+        let complete: MorphismClasses<M> = raw_iso_classes
+            .iter()
+            .map(|raw_iso_class| {
+                raw_iso_class
+                    .iter()
+                    .map(|endo| endo.morphism().clone())
+                    .collect()
+            })
+            .collect();
+        checkpoint.save(|bytes| Progress::<O, M>::persist(&[&complete], bytes))?;
+
+        let buffer: Vec<IsoClass<O, M>> =
+            raw_iso_classes.into_par_iter().map(Self::clean).collect();
+        let mut iso_classes = Self {
+            buffer,
+            filter: filter.name(),
+            wrapper: PhantomData::<W>,
+        };
+        iso_classes.order_classes();
+        Ok(iso_classes)
+    }
+}
+
 impl<
         O: Object + Hash + Display + PrettyName,
         M: Morphism<O> + Debug + IsMap<O> + IsBij<O> + IsMatching<O> + PrettyName,
@@ -184,19 +569,14 @@ impl<
     > Display for IsoClasses<O, M, W>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let number_of_endos = self.buffer.iter().fold(0, |curr_no_out, iso_class| {
-            curr_no_out
-                + iso_class
-                    .values()
-                    .fold(0, |curr_no_in, endos: &Vec<M>| curr_no_in + endos.len())
-        });
+        let number_of_endos = self.number_of_endomorphisms();
 
         let mut string = String::new();
 
-        string.push_str(format!("Functor name: {}\nObject: {}\nMorphism: {}\nNumber of endomorphisms: {}\nNumber of classes: {}\nEvery class has a map: {}\nEvery class has a bijection: {}\nEvery class has exactly one bijection: {}\n===\n", Self::PRETTY_NAME, O::PRETTY_NAME, M::PRETTY_NAME, number_of_endos, self.buffer.len(), self.map_in_every_class(), self.bijection_in_every_class(), self.one_bijection_in_every_class()).as_str());
+        string.push_str(format!("Functor name: {}\nObject: {}\nMorphism: {}\nEndomorphism filter: {}\nNumber of endomorphisms: {}\nNumber of classes: {}\nEvery class has a map: {}\nEvery class has a bijection: {}\nEvery class has exactly one bijection: {}\n===\n", Self::PRETTY_NAME, O::PRETTY_NAME, M::PRETTY_NAME, self.filter, number_of_endos, self.buffer.len(), self.map_in_every_class(), self.bijection_in_every_class(), self.one_bijection_in_every_class()).as_str());
 
-        for iso_class in &self.buffer {
-            string.push_str("---\n");
+        for (position, iso_class) in self.buffer.iter().enumerate() {
+            string.push_str(format!("--- {}\n", ClassId(position)).as_str());
             for (object, endomorphisms) in iso_class {
                 string.push_str(format!("-\n{object}:\n").as_str());
                 for endomorphism in endomorphisms {
@@ -209,6 +589,95 @@ impl<
     }
 }
 
+/**
+objects of a class are sorted, since the order of the hash maps is not stable between runs.
+every class is written with its id, which is its position
+*/
+impl<
+        O: Object + Hash + ToJson + PrettyName,
+        M: Morphism<O> + ToJson + IsMap<O> + IsBij<O> + IsMatching<O> + PrettyName,
+        W: Wrapper<O, M>,
+    > ToJson for IsoClasses<O, M, W>
+{
+    fn to_json(&self) -> Json {
+        let classes = self
+            .buffer
+            .iter()
+            .enumerate()
+            .map(|(position, iso_class)| {
+                let mut objects: Vec<(String, Json)> = iso_class
+                    .iter()
+                    .map(|(object, endomorphisms)| {
+                        (
+                            object.to_json().to_string(),
+                            Json::object([
+                                ("object", object.to_json()),
+                                ("endomorphisms", endomorphisms.to_json()),
+                            ]),
+                        )
+                    })
+                    .collect();
+                objects.sort_by(|left, right| left.0.cmp(&right.0));
+                Json::object([
+                    ("id", ClassId(position).0.to_json()),
+                    (
+                        "objects",
+                        Json::Array(objects.into_iter().map(|object| object.1).collect()),
+                    ),
+                ])
+            })
+            .collect();
+
+        Json::object([
+            ("functor", Self::PRETTY_NAME.to_json()),
+            ("object", O::PRETTY_NAME.to_json()),
+            ("morphism", M::PRETTY_NAME.to_json()),
+            ("endomorphism_filter", self.filter.to_json()),
+            (
+                "number_of_endomorphisms",
+                self.number_of_endomorphisms().to_json(),
+            ),
+            ("number_of_classes", self.buffer.len().to_json()),
+            ("every_class_has_a_map", self.map_in_every_class().to_json()),
+            (
+                "every_class_has_a_bijection",
+                self.bijection_in_every_class().to_json(),
+            ),
+            (
+                "every_class_has_exactly_one_bijection",
+                self.one_bijection_in_every_class().to_json(),
+            ),
+            ("classes", Json::Array(classes)),
+        ])
+    }
+}
+
+impl<O: Object + Hash, M: Morphism<O>, W: Wrapper<O, M>> IsoClasses<O, M, W> {
+    /**
+    sorts the classes by the least encoding of their members,
+    so their ids depend neither on the order of the hash maps nor on the recursion.
+    the classes of the morphisms which cannot be stored are left in the order they were found
+    */
+    pub fn order_classes(&mut self) {
+        self.buffer
+            .sort_by_cached_key(|iso_class: &IsoClass<O, M>| {
+                iso_class
+                    .values()
+                    .flatten()
+                    .filter_map(OrderKey::order_key)
+                    .min()
+            });
+    }
+
+    pub fn number_of_endomorphisms(&self) -> usize {
+        self.buffer
+            .iter()
+            .flat_map(HashMap::values)
+            .map(Vec::len)
+            .sum()
+    }
+}
+
 impl<O: Object + Hash, M: Morphism<O>, W: Wrapper<O, M>> PrettyName for IsoClasses<O, M, W> {
     default const PRETTY_NAME: &'static str = "Not specified";
 }
@@ -247,7 +716,7 @@ impl<O: Object + Hash, M: Morphism<O> + IsBij<O>, W: Wrapper<O, M>> IsoClasses<O
 //-----------------------------------------------------------------------------------------
 pub trait WrapperFull<O: Object + Hash + Clone, M: Morphism<O>>: Wrapper<O, M> + Clone {
     type Isos: Send + Sync; //i cannot resolve this in any other way :(
-    fn all_isos(left: &Self, right: &Self, category: &Category<O, M>) -> Self::Isos;
+    fn all_isos<C: HomSets<O, M>>(left: &Self, right: &Self, category: &C) -> Self::Isos;
 }
 
 #[derive(Clone)]
@@ -267,10 +736,38 @@ unsafe impl<O: Object + Hash + Clone + Sync, M: Morphism<O> + Sync, W: WrapperFu
 {
 }
 
+impl<O: Object + Hash + Clone, M: Morphism<O> + Debug, W: WrapperFull<O, M>> Debug
+    for IsoPair<O, M, W>
+where
+    W::Isos: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IsoPair")
+            .field("left", &self.left)
+            .field("right", &self.right)
+            .field("isos", &self.isos)
+            .finish()
+    }
+}
+
 pub type IsoClassFull<O, M, W> = Vec<IsoPair<O, M, W>>;
 
 pub struct IsoClassesFull<O: Object + Hash + Clone, M: Morphism<O>, W: WrapperFull<O, M>> {
     pub buffer: Vec<IsoClassFull<O, M, W>>,
+    pub filter: &'static str,
+}
+
+impl<O: Object + Hash + Clone, M: Morphism<O> + Debug, W: WrapperFull<O, M>> Debug
+    for IsoClassesFull<O, M, W>
+where
+    W::Isos: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IsoClassesFull")
+            .field("buffer", &self.buffer)
+            .field("filter", &self.filter)
+            .finish()
+    }
 }
 
 impl<
@@ -279,19 +776,22 @@ impl<
         W: WrapperFull<O, M> + Sync + Send,
     > IsoClassesFull<O, M, W>
 {
-    pub fn all_isos(iso_classes: IsoClasses<O, M, W>, category: &Category<O, M>) -> Self {
+    pub fn all_isos<C: HomSets<O, M>>(iso_classes: IsoClasses<O, M, W>, category: &C) -> Self {
         let buffer: Vec<_> = iso_classes
             .buffer
             .into_iter()
             .map(|iso_class: IsoClass<O, M>| Self::all_isos_class(iso_class, category))
             .collect();
 
-        Self { buffer }
+        Self {
+            buffer,
+            filter: iso_classes.filter,
+        }
     }
 
-    fn all_isos_class(
+    fn all_isos_class<C: HomSets<O, M>>(
         iso_class: IsoClass<O, M>,
-        category: &Category<O, M>,
+        category: &C,
     ) -> IsoClassFull<O, M, W> {
         //
         let endos: Vec<M> = iso_class
@@ -351,10 +851,11 @@ impl<
 
         string.push_str(
             format!(
-                "Functor name: {}\nObject: {}\nMorphism: {}\nNumber of classes: {}\n===\n",
+                "Functor name: {}\nObject: {}\nMorphism: {}\nEndomorphism filter: {}\nNumber of classes: {}\n===\n",
                 Self::PRETTY_NAME,
                 O::PRETTY_NAME,
                 M::PRETTY_NAME,
+                self.filter,
                 self.buffer.len()
             )
             .as_str(),
@@ -369,6 +870,23 @@ impl<
         write!(f, "{string}")
     }
 }
+impl<O: Object + Hash + Clone + PrettyName, M: Morphism<O> + PrettyName, W: WrapperFull<O, M>>
+    ToJson for IsoClassesFull<O, M, W>
+where
+    IsoPair<O, M, W>: ToJson,
+{
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("functor", Self::PRETTY_NAME.to_json()),
+            ("object", O::PRETTY_NAME.to_json()),
+            ("morphism", M::PRETTY_NAME.to_json()),
+            ("endomorphism_filter", self.filter.to_json()),
+            ("number_of_classes", self.buffer.len().to_json()),
+            ("classes", self.buffer.to_json()),
+        ])
+    }
+}
+
 impl<O: Object + Hash + Clone, M: Morphism<O>, W: WrapperFull<O, M>> PrettyName
     for IsoClassesFull<O, M, W>
 {
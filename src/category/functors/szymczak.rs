@@ -2,8 +2,9 @@ use crate::category::{
     functors::{IsoClasses, IsoClassesFull, IsoPair, Wrapper, WrapperFull},
     morphism::Endo as Morphism,
     object::Object,
-    Category, PrettyName,
+    HomSets, PrettyName,
 };
+use crate::util::json::{Json, ToJson};
 use std::{
     borrow::Borrow,
     fmt::{self, Debug, Display},
@@ -14,10 +15,28 @@ use std::{
 pub struct Szymczak<O: Object + Hash, M: Morphism<O>> {
     morphism: M,
     cycle: Vec<M>,
+    period: usize,
     object_type: PhantomData<O>,
 }
 
 impl<O: Object + Hash, M: Morphism<O>> Szymczak<O, M> {
+    /**
+    the powers repeat from the one following the last power of the cycle,
+    which is equal to one of the earlier ones
+    */
+    fn period(morphism: &M, cycle: &[M]) -> usize {
+        let next = cycle
+            .last()
+            .expect("the cycle contains the identity")
+            .compose(morphism);
+        cycle.len().saturating_sub(
+            cycle
+                .iter()
+                .position(|power| *power == next)
+                .expect("the powers repeat after the cycle"),
+        )
+    }
+
     fn is_identity(morphism: &M, cycle: &Vec<M>) -> bool {
         for en in cycle {
             let en_morphism = morphism.compose(en);
@@ -33,9 +52,18 @@ impl<O: Object + Hash, M: Morphism<O>> Szymczak<O, M> {
     }
 }
 
+/**
+isomorphic endomorphisms have the same period of their powers.
+if `phi psi f^n = f^m` and the powers of `g` repeat every `q` steps from `g^a` onwards,
+then `f^(m + a + q) = phi g^(n + a + q) psi = phi g^(n + a) psi = f^(m + a)`,
+so the period of `f` divides `q`, and the other way round
+*/
 impl<O: Object + Hash, M: Morphism<O>> Wrapper<O, M> for Szymczak<O, M> {
+    type Invariant = usize;
+
     fn from_morphism(morphism: M) -> Option<Self> {
         morphism.try_cycle().map(|cycle| Self {
+            period: Self::period(&morphism, &cycle),
             morphism,
             cycle,
             object_type: PhantomData::<O>,
@@ -44,27 +72,23 @@ impl<O: Object + Hash, M: Morphism<O>> Wrapper<O, M> for Szymczak<O, M> {
     fn into_morphism(self) -> M {
         self.morphism
     }
+    fn morphism(&self) -> &M {
+        &self.morphism
+    }
+
+    fn invariant(&self) -> Self::Invariant {
+        self.period
+    }
 
-    fn are_isomorphic(left: &Self, right: &Self, category: &Category<O, M>) -> bool {
+    fn are_isomorphic<C: HomSets<O, M>>(left: &Self, right: &Self, category: &C) -> bool {
         let l: &M = &left.morphism;
         let r: &M = &right.morphism;
 
-        let morphisms_l_to_r: &Vec<M> = category
-            .hom_sets
-            .get(l.target().borrow())
-            .expect("There are hom-sets with the given target")
-            .get(r.source().borrow())
-            .expect("There is a hom-set with a given source");
-
-        let morphisms_r_to_l: &Vec<M> = category
-            .hom_sets
-            .get(r.target().borrow())
-            .expect("There are hom-sets with the given target")
-            .get(l.source().borrow())
-            .expect("There is a hom-set with a given source");
-
-        for l_to_r in morphisms_l_to_r {
-            for r_to_l in morphisms_r_to_l {
+        let morphisms_l_to_r = category.hom(l.target().borrow(), r.source().borrow());
+        let morphisms_r_to_l = category.hom(r.target().borrow(), l.source().borrow());
+
+        for l_to_r in morphisms_l_to_r.iter() {
+            for r_to_l in morphisms_r_to_l.iter() {
                 if
                 //l -> r
                 l_to_r.compose(r) == l.compose(l_to_r)
@@ -95,6 +119,7 @@ impl<O: Object + Hash + Clone, M: Morphism<O> + Clone> Clone for Szymczak<O, M>
         Self {
             morphism: self.morphism.clone(),
             cycle: self.cycle.clone(),
+            period: self.period,
             object_type: PhantomData::<O>,
         }
     }
@@ -128,23 +153,12 @@ impl<O: Object + Hash + Clone + Send + Sync, M: Morphism<O> + Clone + Send + Syn
        where (k,l), (k',l') are minimal for (phi, psi)
     */
     type Isos = Vec<((M, M), (usize, usize), (usize, usize))>;
-    fn all_isos(left: &Self, right: &Self, category: &Category<O, M>) -> Self::Isos {
+    fn all_isos<C: HomSets<O, M>>(left: &Self, right: &Self, category: &C) -> Self::Isos {
         let l: &M = &left.morphism;
         let r: &M = &right.morphism;
 
-        let morphisms_l_to_r: &Vec<M> = category
-            .hom_sets
-            .get(l.target().borrow())
-            .expect("There are hom-sets with the given target")
-            .get(r.source().borrow())
-            .expect("There is a hom-set with a given source");
-
-        let morphisms_r_to_l: &Vec<M> = category
-            .hom_sets
-            .get(r.target().borrow())
-            .expect("There are hom-sets with the given target")
-            .get(l.source().borrow())
-            .expect("There is a hom-set with a given source");
+        let morphisms_l_to_r = category.hom(l.target().borrow(), r.source().borrow());
+        let morphisms_r_to_l = category.hom(r.target().borrow(), l.source().borrow());
 
         morphisms_l_to_r
             .iter()
@@ -239,13 +253,62 @@ impl<O: Object + Hash + Display + Clone + Send + Sync, M: Morphism<O> + Debug +
     }
 }
 
+impl<O: Object + Hash + Clone + Send + Sync, M: Morphism<O> + ToJson + Send + Sync> ToJson
+    for IsoPair<O, M, Szymczak<O, M>>
+{
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("left", self.left.to_json()),
+            (
+                "left_cycle_length",
+                self.left
+                    .try_cycle()
+                    .expect("it should be an endomorphism")
+                    .len()
+                    .to_json(),
+            ),
+            ("right", self.right.to_json()),
+            (
+                "right_cycle_length",
+                self.right
+                    .try_cycle()
+                    .expect("it should be an endomorphism")
+                    .len()
+                    .to_json(),
+            ),
+            (
+                "isos",
+                Json::Array(
+                    self.isos
+                        .iter()
+                        .map(|&((ref phi, ref psi), left_powers, right_powers)| {
+                            Json::object([
+                                ("phi", phi.to_json()),
+                                ("psi", psi.to_json()),
+                                ("left_powers", left_powers.to_json()),
+                                ("right_powers", right_powers.to_json()),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
 //-----------------------------------------------------------------------------------------
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::{
-        category::{morphism::Morphism, object::Concrete, relation::Relation, Category},
+        category::{
+            functors::EndoFilter,
+            morphism::{Dagger, Endorelation, IsBij, IsMap, Morphism},
+            object::Concrete,
+            relation::Relation,
+            Category,
+        },
         ralg::{
             cgroup::{ideal::CIdeal, C},
             module::canon::object::Object as Module,
@@ -257,6 +320,26 @@ mod test {
     type I = CIdeal<N>;
     type W = Szymczak<Module<R, I>, Relation<R, I>>;
 
+    // every class as the sorted list of its endomorphisms
+    fn describe<O: Object + Hash, M: crate::category::morphism::Endo<O> + Debug>(
+        classes: &SzymczakClasses<O, M>,
+    ) -> Vec<Vec<String>> {
+        let mut described: Vec<Vec<String>> = classes
+            .buffer
+            .iter()
+            .map(|class| {
+                let mut endos: Vec<String> = class
+                    .values()
+                    .flat_map(|endos| endos.iter().map(|endo| format!("{endo:?}")))
+                    .collect();
+                endos.sort();
+                endos
+            })
+            .collect();
+        described.sort();
+        described
+    }
+
     #[test]
     fn szymczak_isomorphism_is_equivalence() {
         use typenum::{Unsigned, U5 as N};
@@ -361,6 +444,36 @@ mod test {
         assert!(is_sometimes_true);
     }
 
+    #[test]
+    fn daggers_of_isomorphic_endos_are_isomorphic() {
+        let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
+        let zn: Module<R, I> = category
+            .clone()
+            .into_objects()
+            .into_iter()
+            .find(|object| object.cardinality() == N::to_usize())
+            .expect("there is a module of given cardinality");
+        let wrapped: Vec<(W, W)> = category
+            .hom_set(&zn, &zn)
+            .into_iter()
+            .map(|endo| {
+                (
+                    W::from_morphism(endo.dagger()).unwrap(),
+                    W::from_morphism(endo).unwrap(),
+                )
+            })
+            .collect();
+
+        for left in &wrapped {
+            for right in &wrapped {
+                assert_eq!(
+                    W::are_isomorphic(&left.1, &right.1, &category),
+                    W::are_isomorphic(&left.0, &right.0, &category)
+                );
+            }
+        }
+    }
+
     #[test]
     fn is_identity() {
         let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
@@ -484,7 +597,7 @@ mod test {
 
                 let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
                 let szymczak_classes =
-                    SzymczakClasses::<Module<R, I>, Relation<R, I>>::functor::<20>(&category);
+                    SzymczakClasses::<Module<R, I>, Relation<R, I>>::functor(&category, 20);
                 assert_eq!(szymczak_classes.buffer.len(), p);
             }
         };
@@ -495,4 +608,218 @@ mod test {
     generate_test_szymczak_functor_zp!(szymczak_functor_z5, U5);
     generate_test_szymczak_functor_zp!(szymczak_functor_z7, U7);
     generate_test_szymczak_functor_zp!(szymczak_functor_z11, U11);
+
+    #[test]
+    fn isomorphic_endomorphisms_have_the_same_period() {
+        let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
+        let endos: Vec<W> = category
+            .clone()
+            .into_objects()
+            .iter()
+            .flat_map(|object| category.hom(object, object).iter())
+            .map(|endo| W::from_morphism(endo.clone()).expect("this is an endomorphism"))
+            .collect();
+
+        for left in &endos {
+            for right in &endos {
+                if W::are_isomorphic(left, right, &category) {
+                    assert_eq!(left.invariant(), right.invariant());
+                }
+            }
+        }
+        // the multiplications by the elements of order 4 and by the one of order 2
+        assert_eq!(endos.iter().filter(|endo| endo.invariant() == 4).count(), 2);
+        assert_eq!(endos.iter().filter(|endo| endo.invariant() == 2).count(), 1);
+    }
+
+    #[test]
+    fn classes_do_not_depend_on_the_recursion() {
+        use typenum::U4;
+        type R = C<U4>;
+        type I = CIdeal<U4>;
+        type Classes = SzymczakClasses<Module<R, I>, Relation<R, I>>;
+
+        let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
+        let expected = describe(&Classes::functor_filtered(&category, 1000, EndoFilter::All));
+        for recursion_parameter in [2, 3, 5, 8] {
+            assert_eq!(
+                describe(&Classes::functor_filtered(
+                    &category,
+                    recursion_parameter,
+                    EndoFilter::All
+                )),
+                expected,
+                "recursion parameter {recursion_parameter}"
+            );
+        }
+    }
+
+    #[test]
+    fn filtering_endomorphisms() {
+        type Classes = SzymczakClasses<Module<R, I>, Relation<R, I>>;
+
+        let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
+        let nof_endos: usize = category
+            .clone()
+            .into_objects()
+            .iter()
+            .map(|object| category.hom(object, object).len())
+            .sum();
+
+        let all = Classes::functor_filtered(&category, 2, EndoFilter::All);
+        assert_eq!(all.number_of_endomorphisms(), nof_endos);
+        assert_eq!(all.filter, "all");
+        assert!(all.to_string().contains("Endomorphism filter: all\n"));
+
+        let maps = Classes::functor_filtered(&category, 2, EndoFilter::Maps);
+        let bijections = Classes::functor_filtered(&category, 2, EndoFilter::Bijections);
+        let endos = |classes: &Classes| {
+            classes
+                .buffer
+                .iter()
+                .flat_map(|class| class.values().flatten().cloned())
+                .collect::<Vec<_>>()
+        };
+        assert!(endos(&maps).iter().all(IsMap::is_a_map));
+        assert!(endos(&bijections).iter().all(IsBij::is_a_bijection));
+        assert!(bijections.number_of_endomorphisms() < maps.number_of_endomorphisms());
+
+        let equivalences = Classes::functor_by(
+            &category,
+            2,
+            "equivalences",
+            Endorelation::is_an_equivalence,
+        );
+        assert_eq!(equivalences.filter, "equivalences");
+        assert!(endos(&equivalences)
+            .iter()
+            .all(Endorelation::is_an_equivalence));
+        // the identity and the full relation of every object, the zero one has only one
+        assert_eq!(equivalences.number_of_endomorphisms(), 3);
+    }
+
+    #[test]
+    fn classifying_endomorphisms() {
+        use crate::category::functors::index::{ClassId, ClassIndex};
+        use typenum::U2;
+
+        type Classes = SzymczakClasses<Module<R, I>, Relation<R, I>>;
+        type S = C<U2>;
+        type J = CIdeal<U2>;
+        type Classes2 = SzymczakClasses<Module<S, J>, Relation<S, J>>;
+
+        let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
+        let classes = Classes::functor_filtered(&category, 2, EndoFilter::All);
+        let again = Classes::functor_filtered(&category, 3, EndoFilter::All);
+
+        let index = ClassIndex::new(&classes);
+        assert_eq!(index.len(), classes.buffer.len());
+        for (position, (class, class_again)) in classes.buffer.iter().zip(&again.buffer).enumerate()
+        {
+            for endo in class.values().flatten() {
+                assert_eq!(index.classify(endo, &category), Some(ClassId(position)));
+                assert_eq!(classes.classify(endo, &category), Some(ClassId(position)));
+                assert!(
+                    class_again.values().flatten().any(|other| other == endo),
+                    "ordered classes do not depend on the recursion"
+                );
+            }
+        }
+        let objects = category.clone().into_objects();
+        let (zero, zn) = (
+            objects.iter().find(|object| object.cardinality() == 1),
+            objects
+                .iter()
+                .find(|object| object.cardinality() == N::to_usize()),
+        );
+        let not_an_endo = category
+            .hom(
+                zero.expect("there is the zero module"),
+                zn.expect("there is zn module"),
+            )
+            .first()
+            .expect("there is a relation between any two modules");
+        assert_eq!(index.classify(not_an_endo, &category), None);
+
+        // the endomorphisms of the plane over Z2 are classified by the ones of the line
+        let line_classes = Classes2::functor_filtered(&Category::new(1), 2, EndoFilter::All);
+
+        let path = std::env::temp_dir().join(format!("szymczak-index-{}", std::process::id()));
+        ClassIndex::new(&line_classes)
+            .save(&path, "Z2")
+            .expect("temporary directory is writable");
+        assert!(
+            ClassIndex::<Module<S, J>, Relation<S, J>, Szymczak<_, _>>::load(&path, "Z4").is_err(),
+            "the index is over another ring"
+        );
+        let line_index: ClassIndex<_, _, Szymczak<_, _>> =
+            ClassIndex::load(&path, "Z2").expect("index is readable");
+        std::fs::remove_file(&path).expect("index is removable");
+        assert_eq!(line_index.filter, "all");
+
+        let plane_category = Category::<Module<S, J>, Relation<S, J>>::new(2);
+        for object in plane_category.clone().into_objects() {
+            for endo in plane_category.hom(&object, &object) {
+                let class = line_index.classify(endo, &plane_category);
+                let wrapped =
+                    Szymczak::from_morphism(endo.clone()).expect("this is an endomorphism");
+                if object.cardinality() <= 2 {
+                    assert_eq!(
+                        class,
+                        line_classes.classify(endo, &Category::new(1)),
+                        "{endo:?}"
+                    );
+                }
+                if wrapped.invariant() == 3 {
+                    assert_eq!(class, None, "no endomorphism of the line has period 3");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn resuming_szymczak_functor() {
+        use crate::{category::functors::Progress, util::checkpoint::Checkpoint};
+        use std::{collections::HashMap, time::Duration};
+
+        type Classes = SzymczakClasses<Module<R, I>, Relation<R, I>>;
+
+        let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
+        let expected = Classes::functor(&category, 2);
+
+        // a run killed after finding the classes of a half of the endomorphisms
+        let mut endos = Classes::endos(&category, &|endo: &Relation<R, I>| {
+            EndoFilter::Wide.admits(endo)
+        });
+        endos.truncate(endos.len() / 2);
+        let half: Vec<Vec<Relation<R, I>>> =
+            Classes::raw_functor(endos, &category, 2, 1, &|_part, _classes| {})
+                .iter()
+                .map(|class| class.iter().map(|endo| endo.morphism().clone()).collect())
+                .collect();
+
+        let path = std::env::temp_dir().join(format!(
+            "szymczak-classes-{}.checkpoint",
+            std::process::id()
+        ));
+        let checkpoint = Checkpoint::new(path, "Z5 dim 1".to_owned(), Duration::ZERO, true);
+        checkpoint
+            .save(|bytes| Progress::<Module<R, I>, Relation<R, I>>::persist(&[&half], bytes))
+            .expect("temporary directory is writable");
+
+        let resumed = Classes::functor_checkpointed(&category, 2, EndoFilter::Wide, &checkpoint)
+            .expect("checkpoint is readable");
+        assert_eq!(describe(&resumed), describe(&expected));
+
+        // the complete classes are saved, so resuming again finds nothing to do
+        let again = Classes::functor_checkpointed(&category, 2, EndoFilter::Wide, &checkpoint)
+            .expect("checkpoint is readable");
+        assert_eq!(describe(&again), describe(&expected));
+        assert_eq!(
+            again.buffer.iter().map(HashMap::len).sum::<usize>(),
+            expected.buffer.iter().map(HashMap::len).sum::<usize>()
+        );
+
+        checkpoint.remove().expect("checkpoint is removable");
+    }
 }
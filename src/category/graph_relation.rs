@@ -0,0 +1,438 @@
+use crate::{
+    category::{
+        morphism::{
+            Concrete as ConcreteMorphism, Dagger, Endo as EndoMorphism,
+            Enumerable as EnumerableMorphism, IsBij, IsMap, IsMatching, IsWide, Morphism,
+            PreAbelian,
+        },
+        object::Concrete as ConcreteObject,
+        relation::Relation,
+        PrettyName,
+    },
+    ralg::{
+        cgroup::{ideal::CIdeal, Radix, C},
+        matrix::Matrix,
+        module::{
+            canon::{element::Element as CanonElement, object::Object as CanonModule},
+            direct::Object as DirectModule,
+            map::CanonToCanon,
+        },
+        ring::{
+            ideal::Principal as PrincipalIdeal, AdditivePartialGroup, AdditivePartialMonoid,
+            Bezout as BezoutRing, Factorial as FactorialRing, Ring,
+        },
+    },
+};
+use std::{fmt, hash, sync::Arc};
+use typenum::{IsGreater, U1};
+
+/**
+an additive relation `a -> b` kept as its graph, the inclusion of a submodule of `a + b`.
+unlike `Relation`, the size of this does not depend on the number of elements of `a` and `b`,
+so it can be used for the modules whose relations would not fit into a matrix of bits.
+the inclusion is not unique, so two graphs are equal if they have the same image
+*/
+#[derive(Clone)]
+pub struct GraphRelation<R: Ring, I: PrincipalIdeal<Parent = R> + Ord> {
+    direct: DirectModule<R, I>,
+    pub graph: CanonToCanon<R, I>,
+}
+
+/**
+the coordinates of `a + b` are ordered by the uuids of the coefficients,
+which the sum uses to tell `a` and `b` apart,
+so a module is never added to itself, but to its duplicate
+*/
+fn direct_sum<R: FactorialRing + Copy, I: PrincipalIdeal<Parent = R> + Ord>(
+    left: &Arc<CanonModule<R, I>>,
+    right: &Arc<CanonModule<R, I>>,
+) -> DirectModule<R, I> {
+    match left.iter().any(|mark| right.contains(mark)) {
+        true => DirectModule::sumproduct(left, &Arc::new(right.duplicate())),
+        false => DirectModule::sumproduct(left, right),
+    }
+}
+
+/**
+an isomorphism from a duplicate of the module, to be composed with the maps out of it
+*/
+fn from_duplicate<R: Ring + Copy, I: PrincipalIdeal<Parent = R> + Ord>(
+    module: &Arc<CanonModule<R, I>>,
+) -> CanonToCanon<R, I> {
+    let dim = module.dimension();
+    CanonToCanon::new(
+        &Arc::new(module.duplicate()),
+        module,
+        Matrix::identity(dim, dim),
+    )
+}
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    GraphRelation<R, I>
+{
+    /**
+    the graph of the submodule, which should be mono into the sum
+    */
+    pub const fn new(direct: DirectModule<R, I>, graph: CanonToCanon<R, I>) -> Self {
+        Self { direct, graph }
+    }
+
+    /**
+    the jointly monic span `a <- s -> b` given by the projections restricted to the graph
+    */
+    pub fn legs(&self) -> (CanonToCanon<R, I>, CanonToCanon<R, I>) {
+        (
+            self.graph.compose(&self.direct.left_projection),
+            self.graph.compose(&self.direct.right_projection),
+        )
+    }
+
+    pub fn contains(
+        &self,
+        source_element: CanonElement<R, I>,
+        target_element: CanonElement<R, I>,
+    ) -> bool {
+        self.contains_with(&self.graph.cokernel(), source_element, target_element)
+    }
+
+    /**
+    `contains` with the cokernel of the graph given, so that it is computed once for many pairs
+    */
+    #[allow(clippy::expect_used, reason = "structural properties")]
+    fn contains_with(
+        &self,
+        cokernel: &CanonToCanon<R, I>,
+        source_element: CanonElement<R, I>,
+        target_element: CanonElement<R, I>,
+    ) -> bool {
+        self.direct
+            .left_inclusion
+            .try_evaluate(source_element)
+            .zip(self.direct.right_inclusion.try_evaluate(target_element))
+            .and_then(|(left, right)| left.try_add(right))
+            .is_some_and(|element| {
+                cokernel
+                    .try_evaluate(element)
+                    .expect("element of the sum")
+                    .is_zero()
+            })
+    }
+
+    #[allow(clippy::expect_used, reason = "structural properties")]
+    pub fn is_contained_in(&self, other: &Self) -> bool {
+        if self.source() != other.source() || self.target() != other.target() {
+            return false;
+        }
+        let (left, right) = self.legs();
+        let submodule = self.graph.source();
+        let cokernel = other.graph.cokernel();
+        let contained = submodule.iter().all(|mark| {
+            other.contains_with(
+                &cokernel,
+                left.try_evaluate(submodule.versor(mark))
+                    .expect("versor of the graph"),
+                right
+                    .try_evaluate(submodule.versor(mark))
+                    .expect("versor of the graph"),
+            )
+        });
+        contained
+    }
+
+    /**
+    the dense matrix of the relation, this is as expensive as enumerating the graph
+    */
+    #[allow(
+        clippy::expect_used,
+        reason = "the legs have the graph as their source"
+    )]
+    pub fn to_relation(&self) -> Relation<R, I> {
+        let (left, right) = self.legs();
+        Relation::try_from_span(&left, &right).expect("the legs have the same source")
+    }
+}
+
+/* ## conversions */
+
+impl<Period: Radix + IsGreater<U1> + Send + Sync> From<&Relation<C<Period>, CIdeal<Period>>>
+    for GraphRelation<C<Period>, CIdeal<Period>>
+{
+    fn from(relation: &Relation<C<Period>, CIdeal<Period>>) -> Self {
+        // the same sum as the one the submodule is found in
+        Self {
+            direct: DirectModule::sumproduct(&relation.source, &relation.target),
+            graph: relation.submodule(),
+        }
+    }
+}
+
+/* ## debug, equality and hashing */
+
+impl<R: Ring + fmt::Debug, I: PrincipalIdeal<Parent = R> + Ord + fmt::Debug> fmt::Debug
+    for GraphRelation<R, I>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.graph)
+    }
+}
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    PartialEq for GraphRelation<R, I>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.graph.source().cardinality() == other.graph.source().cardinality()
+            && self.is_contained_in(other)
+            && other.is_contained_in(self)
+    }
+}
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord> Eq
+    for GraphRelation<R, I>
+{
+}
+
+// equal graphs are isomorphic, so they have the same number of elements
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    hash::Hash for GraphRelation<R, I>
+{
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.source().cardinality().hash(state);
+        self.target().cardinality().hash(state);
+        self.graph.source().cardinality().hash(state);
+    }
+}
+
+impl<R: Ring, I: PrincipalIdeal<Parent = R> + Ord> PrettyName for GraphRelation<R, I> {
+    const PRETTY_NAME: &'static str = "Graph relation";
+}
+
+/* ## morphism */
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    Morphism<CanonModule<R, I>> for GraphRelation<R, I>
+{
+    type B = Arc<CanonModule<R, I>>;
+
+    fn source(&self) -> Self::B {
+        self.direct.left()
+    }
+
+    fn target(&self) -> Self::B {
+        self.direct.right()
+    }
+
+    /**
+    the graph of the composite is the image of the pullback of the graphs over the middle object.
+    this assumes that the target of self is the source of other
+    */
+    #[allow(clippy::expect_used, reason = "structural properties")]
+    fn compose(&self, other: &Self) -> Self {
+        let (self_source, self_target) = self.legs();
+        let (other_source, other_target) = other.legs();
+        let self_graph = from_duplicate(&self.graph.source());
+        let other_graph = from_duplicate(&other.graph.source());
+        let pair = DirectModule::sumproduct(&self_graph.source(), &other_graph.source());
+        let to_self = pair.left_projection.compose(&self_graph);
+        let to_other = pair.right_projection.compose(&other_graph);
+
+        let pullback = to_self
+            .compose(&self_target)
+            .try_sub(to_other.compose(&other_source))
+            .expect("the middle objects should be the same")
+            .kernel();
+        let direct = direct_sum(&self.source(), &other.target());
+        let graph = pullback
+            .compose(&to_self)
+            .compose(&self_source)
+            .compose(&direct.left_inclusion)
+            .try_add(
+                pullback
+                    .compose(&to_other)
+                    .compose(&other_target)
+                    .compose(&direct.right_inclusion),
+            )
+            .expect("both maps go from the pullback to the sum");
+
+        Self {
+            graph: PreAbelian::image(&graph),
+            direct,
+        }
+    }
+}
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    EndoMorphism<CanonModule<R, I>> for GraphRelation<R, I>
+{
+    #[allow(
+        clippy::expect_used,
+        reason = "both inclusions go from the object to the sum"
+    )]
+    fn identity(object: Self::B) -> Self {
+        let direct = direct_sum(&object, &object);
+        let diagonal = direct
+            .left_inclusion
+            .clone()
+            .try_add(from_duplicate(&direct.right()).compose(&direct.right_inclusion))
+            .expect("both maps go from the object to the sum");
+        Self {
+            direct,
+            graph: diagonal,
+        }
+    }
+}
+
+impl<Period: Radix + IsGreater<U1> + Send + Sync>
+    EnumerableMorphism<CanonModule<C<Period>, CIdeal<Period>>>
+    for GraphRelation<C<Period>, CIdeal<Period>>
+{
+    fn hom(source: Self::B, target: Self::B) -> impl Iterator<Item = Self> + Clone {
+        let direct = direct_sum(&source, &target);
+        direct
+            .clone()
+            .submodules_goursat()
+            .into_iter()
+            .map(move |graph| Self {
+                direct: direct.clone(),
+                graph,
+            })
+    }
+}
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    Dagger<CanonModule<R, I>> for GraphRelation<R, I>
+{
+    #[allow(
+        clippy::expect_used,
+        reason = "both maps go from the sum to the swapped sum"
+    )]
+    fn dagger(&self) -> Self {
+        let direct = direct_sum(&self.target(), &self.source());
+        let swap = self
+            .direct
+            .left_projection
+            .compose(&direct.right_inclusion)
+            .try_add(self.direct.right_projection.compose(&direct.left_inclusion))
+            .expect("both maps go from the sum to the swapped sum");
+        Self {
+            graph: self.graph.compose(&swap),
+            direct,
+        }
+    }
+}
+
+/* ## properties */
+
+/*
+the relation is defined everywhere when the left leg is epi,
+and it is single valued when the left leg is mono.
+the right leg plays the same role for the converse
+*/
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    IsMap<CanonModule<R, I>> for GraphRelation<R, I>
+{
+    fn is_a_map(&self) -> bool {
+        let (left, _right) = self.legs();
+        left.kernel().is_zero() && left.cokernel().is_zero()
+    }
+}
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    IsMatching<CanonModule<R, I>> for GraphRelation<R, I>
+{
+    fn is_a_matching(&self) -> bool {
+        let (left, right) = self.legs();
+        left.kernel().is_zero() && right.kernel().is_zero()
+    }
+}
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    IsWide<CanonModule<R, I>> for GraphRelation<R, I>
+{
+    fn is_wide(&self) -> bool {
+        let (left, right) = self.legs();
+        left.cokernel().is_zero() && right.cokernel().is_zero()
+    }
+}
+
+impl<R: BezoutRing + FactorialRing + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
+    IsBij<CanonModule<R, I>> for GraphRelation<R, I>
+{
+    fn is_a_bijection(&self) -> bool {
+        self.is_a_matching() && self.is_wide()
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::category::{functors::szymczak::SzymczakClasses, Category};
+    use typenum::U4 as N;
+
+    type R = C<N>;
+    type I = CIdeal<N>;
+    type Zn = CanonModule<R, I>;
+
+    #[test]
+    fn composition_agrees_with_relations() {
+        let relations = Category::<Zn, Relation<R, I>>::new(1).into_morphisms();
+        let graphs: Vec<GraphRelation<R, I>> = relations.iter().map(GraphRelation::from).collect();
+
+        for (relation, graph) in relations.iter().zip(&graphs) {
+            assert_eq!(graph.to_relation(), *relation);
+            assert_eq!(graph.dagger().to_relation(), relation.dagger());
+            assert_eq!(graph.is_a_map(), relation.is_a_map());
+            assert_eq!(graph.is_a_matching(), relation.is_a_matching());
+            assert_eq!(graph.is_wide(), relation.is_wide());
+
+            for (other_relation, other_graph) in relations.iter().zip(&graphs) {
+                if let Some(composite) = relation.try_compose(other_relation) {
+                    assert_eq!(graph.compose(other_graph).to_relation(), composite);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn identities_are_diagonals() {
+        let z2 = Arc::new(Zn::from_iter([2]));
+        let z4 = Arc::new(Zn::from_iter([4]));
+        for object in [z2, z4] {
+            assert_eq!(
+                GraphRelation::<R, I>::identity(Arc::clone(&object)).to_relation(),
+                Relation::identity(object)
+            );
+        }
+    }
+
+    #[test]
+    fn equal_graphs_have_equal_hashes() {
+        use std::collections::HashSet;
+        let relations = Category::<Zn, Relation<R, I>>::new(1).into_morphisms();
+        let graphs: HashSet<GraphRelation<R, I>> =
+            relations.iter().map(GraphRelation::from).collect();
+        assert_eq!(graphs.len(), relations.len());
+
+        // composing with the identity gives another generating set of the same graph
+        for graph in &graphs {
+            let generated_again = graph.compose(&GraphRelation::identity(graph.target()));
+            assert!(graphs.contains(&generated_again));
+        }
+    }
+
+    #[test]
+    fn szymczak_classes_of_graphs() {
+        let relations = Category::<Zn, Relation<R, I>>::new(1);
+        let graphs = Category::<Zn, GraphRelation<R, I>>::new(1);
+
+        let expected = SzymczakClasses::functor(&relations, 2);
+        let classes = SzymczakClasses::functor(&graphs, 2);
+        assert_eq!(classes.buffer.len(), expected.buffer.len());
+        assert_eq!(
+            classes.number_of_endomorphisms(),
+            expected.number_of_endomorphisms()
+        );
+    }
+}
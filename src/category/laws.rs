@@ -0,0 +1,584 @@
+use crate::category::{
+    functors::Functor,
+    morphism::{Allegory, Dagger, Endo as EndoMorphism, Morphism},
+    object::Object,
+    Category,
+};
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::Hash,
+    sync::Arc,
+};
+
+/* # report */
+
+/**
+a broken law may be broken by a great many morphisms,
+only the first few of them are kept as counterexamples
+*/
+pub const MAX_COUNTEREXAMPLES: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violations<T> {
+    pub count: usize,
+    pub counterexamples: Vec<T>,
+}
+
+impl<T> Default for Violations<T> {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            counterexamples: Vec::new(),
+        }
+    }
+}
+
+impl<T> Violations<T> {
+    fn push(&mut self, counterexample: T) {
+        self.count = self.count.saturating_add(1);
+        if self.counterexamples.len() < MAX_COUNTEREXAMPLES {
+            self.counterexamples.push(counterexample);
+        }
+    }
+}
+
+/**
+the number of the violations of every law, under the name it is reported with
+*/
+type Counts<const N: usize> = [(&'static str, usize); N];
+
+fn none_violated(counts: &[(&str, usize)]) -> bool {
+    counts.iter().all(|&(_, count)| count == 0)
+}
+
+fn write_counts(f: &mut fmt::Formatter<'_>, counts: &[(&str, usize)]) -> fmt::Result {
+    for &(law, count) in counts {
+        writeln!(f, "{law}: {count}")?;
+    }
+    Ok(())
+}
+
+/**
+the laws of a category checked on its hom-sets:
+* no hom-set contains the same morphism twice,
+* the identity of every object is in its hom-set of endomorphisms,
+* a composite of `f: a -> b` and `g: b -> c` is in hom(a, c),
+* identities are units on both sides, `id_a f = f = f id_b`,
+* `(f g) h = f (g h)` whenever the composites are defined.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report<M> {
+    pub duplicates: Violations<M>,
+    pub missing_identities: Violations<M>,
+    pub not_closed: Violations<(M, M)>,
+    pub not_unital: Violations<M>,
+    pub not_associative: Violations<(M, M, M)>,
+}
+
+impl<M> Report<M> {
+    const fn counts(&self) -> Counts<5> {
+        [
+            ("Duplicate morphisms", self.duplicates.count),
+            ("Missing identities", self.missing_identities.count),
+            (
+                "Composites outside of their hom-sets",
+                self.not_closed.count,
+            ),
+            ("Identities which are not units", self.not_unital.count),
+            ("Non-associative triples", self.not_associative.count),
+        ]
+    }
+
+    pub fn holds(&self) -> bool {
+        none_violated(&self.counts())
+    }
+}
+
+impl<M> fmt::Display for Report<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_counts(f, &self.counts())
+    }
+}
+
+/**
+the laws of a dagger checked on the hom-sets of a category:
+* the dagger of `f: a -> b` is in hom(b, a),
+* `f^dagger^dagger = f`,
+* `(f g)^dagger = g^dagger f^dagger` whenever the composite is defined,
+* identities are their own daggers.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaggerReport<M> {
+    pub not_reversed: Violations<M>,
+    pub not_involutive: Violations<M>,
+    pub not_contravariant: Violations<(M, M)>,
+    pub identities_not_fixed: Violations<M>,
+}
+
+impl<M> DaggerReport<M> {
+    const fn counts(&self) -> Counts<4> {
+        [
+            (
+                "Daggers outside of the reversed hom-sets",
+                self.not_reversed.count,
+            ),
+            ("Non-involutive morphisms", self.not_involutive.count),
+            ("Non-contravariant pairs", self.not_contravariant.count),
+            (
+                "Identities which are not self-dagger",
+                self.identities_not_fixed.count,
+            ),
+        ]
+    }
+
+    pub fn holds(&self) -> bool {
+        none_violated(&self.counts())
+    }
+}
+
+impl<M> fmt::Display for DaggerReport<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_counts(f, &self.counts())
+    }
+}
+
+/**
+the laws of an allegory with lattices as hom-sets:
+* meets and joins of `f, g: a -> b` are in hom(a, b),
+* the greatest and the least relations are in every hom-set,
+* the modular law, `(f g) & h <= f (g & f^dagger h)` for `f: a -> b`, `g: b -> c` and `h: a -> c`.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AllegoryReport<M> {
+    pub meets_not_closed: Violations<(M, M)>,
+    pub joins_not_closed: Violations<(M, M)>,
+    pub missing_bounds: Violations<M>,
+    pub not_modular: Violations<(M, M, M)>,
+}
+
+impl<M> AllegoryReport<M> {
+    const fn counts(&self) -> Counts<4> {
+        [
+            (
+                "Meets outside of their hom-sets",
+                self.meets_not_closed.count,
+            ),
+            (
+                "Joins outside of their hom-sets",
+                self.joins_not_closed.count,
+            ),
+            (
+                "Missing greatest or least morphisms",
+                self.missing_bounds.count,
+            ),
+            ("Triples breaking the modular law", self.not_modular.count),
+        ]
+    }
+
+    pub fn holds(&self) -> bool {
+        none_violated(&self.counts())
+    }
+}
+
+impl<M> fmt::Display for AllegoryReport<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_counts(f, &self.counts())
+    }
+}
+
+/**
+the laws of a functor checked on the given morphisms:
+* the image of `f: a -> b` goes from the image of `a` to the image of `b`,
+* the images of identities are identities,
+* the image of a composite is the composite of the images.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctorReport<M> {
+    pub misplaced_images: Violations<M>,
+    pub identities_not_preserved: Violations<M>,
+    pub composites_not_preserved: Violations<(M, M)>,
+}
+
+impl<M> FunctorReport<M> {
+    const fn counts(&self) -> Counts<3> {
+        [
+            (
+                "Morphisms with misplaced images",
+                self.misplaced_images.count,
+            ),
+            (
+                "Identities not sent to identities",
+                self.identities_not_preserved.count,
+            ),
+            (
+                "Composites not sent to composites",
+                self.composites_not_preserved.count,
+            ),
+        ]
+    }
+
+    pub fn holds(&self) -> bool {
+        none_violated(&self.counts())
+    }
+}
+
+impl<M> fmt::Display for FunctorReport<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_counts(f, &self.counts())
+    }
+}
+
+/* # checking */
+
+impl<O: Object + Hash, M: Morphism<O>> Category<O, M> {
+    /**
+    the objects and the hom-sets between them, a missing hom-set being empty
+    */
+    fn checked_hom_sets<'category>(
+        &'category self,
+    ) -> (Vec<&'category O>, impl Fn(&O, &O) -> &'category [M]) {
+        (
+            self.hom_sets.keys().collect(),
+            |source: &O, target: &O| -> &'category [M] {
+                self.hom_sets
+                    .get(source)
+                    .and_then(|hom_sets_fixed_source| hom_sets_fixed_source.get(target))
+                    .map_or(&[], Vec::as_slice)
+            },
+        )
+    }
+}
+
+impl<O: Object + Hash + Clone, M: Morphism<O, B = Arc<O>> + EndoMorphism<O>> Category<O, M> {
+    /**
+    every triple of composable morphisms is checked for associativity,
+    so this is meant for the categories small enough to be checked before the functors are run
+    */
+    pub fn verify(&self) -> Report<M> {
+        let mut report = Report {
+            duplicates: Violations::default(),
+            missing_identities: Violations::default(),
+            not_closed: Violations::default(),
+            not_unital: Violations::default(),
+            not_associative: Violations::default(),
+        };
+        let (objects, hom) = self.checked_hom_sets();
+
+        let mut members: HashMap<(&O, &O), HashSet<&M>> = HashMap::new();
+        for &source in &objects {
+            for &target in &objects {
+                let hom_set = members.entry((source, target)).or_default();
+                for morphism in hom(source, target) {
+                    if !hom_set.insert(morphism) {
+                        report.duplicates.push(morphism.clone());
+                    }
+                }
+            }
+        }
+        let is_member = |morphism: &M| {
+            members
+                .get(&(morphism.source().as_ref(), morphism.target().as_ref()))
+                .is_some_and(|hom_set| hom_set.contains(morphism))
+        };
+
+        let identities: HashMap<&O, M> = objects
+            .iter()
+            .map(|&object| (object, M::identity(Arc::new(object.clone()))))
+            .collect();
+        for identity in identities.values() {
+            if !is_member(identity) {
+                report.missing_identities.push(identity.clone());
+            }
+        }
+        let identity = |object| {
+            identities
+                .get(object)
+                .expect("there is an identity on every object")
+        };
+
+        for &source in &objects {
+            for &middle in &objects {
+                for first in hom(source, middle) {
+                    if identity(source).compose(first) != *first
+                        || first.compose(identity(middle)) != *first
+                    {
+                        report.not_unital.push(first.clone());
+                    }
+
+                    for &target in &objects {
+                        for second in hom(middle, target) {
+                            let composite = first.compose(second);
+                            if composite.source().as_ref() != source
+                                || composite.target().as_ref() != target
+                                || !is_member(&composite)
+                            {
+                                report.not_closed.push((first.clone(), second.clone()));
+                            }
+
+                            for &end in &objects {
+                                for third in hom(target, end) {
+                                    if composite.compose(third)
+                                        != first.compose(&second.compose(third))
+                                    {
+                                        report.not_associative.push((
+                                            first.clone(),
+                                            second.clone(),
+                                            third.clone(),
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+impl<O: Object + Hash + Clone, M: Morphism<O, B = Arc<O>> + EndoMorphism<O> + Dagger<O>>
+    Category<O, M>
+{
+    pub fn verify_dagger(&self) -> DaggerReport<M> {
+        let mut report = DaggerReport {
+            not_reversed: Violations::default(),
+            not_involutive: Violations::default(),
+            not_contravariant: Violations::default(),
+            identities_not_fixed: Violations::default(),
+        };
+        let (objects, hom) = self.checked_hom_sets();
+
+        for &object in &objects {
+            let identity = M::identity(Arc::new(object.clone()));
+            if identity.dagger() != identity {
+                report.identities_not_fixed.push(identity);
+            }
+        }
+
+        for &source in &objects {
+            for &middle in &objects {
+                for first in hom(source, middle) {
+                    let first_dagger = first.dagger();
+                    if first_dagger.source().as_ref() != middle
+                        || first_dagger.target().as_ref() != source
+                        || !hom(middle, source).contains(&first_dagger)
+                    {
+                        report.not_reversed.push(first.clone());
+                    }
+                    if first_dagger.dagger() != *first {
+                        report.not_involutive.push(first.clone());
+                    }
+
+                    for &target in &objects {
+                        for second in hom(middle, target) {
+                            if first.compose(second).dagger()
+                                != second.dagger().compose(&first_dagger)
+                            {
+                                report
+                                    .not_contravariant
+                                    .push((first.clone(), second.clone()));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+impl<O: Object + Hash + Clone, M: Morphism<O, B = Arc<O>> + EndoMorphism<O> + Allegory<O>>
+    Category<O, M>
+{
+    pub fn verify_allegory(&self) -> AllegoryReport<M> {
+        let mut report = AllegoryReport {
+            meets_not_closed: Violations::default(),
+            joins_not_closed: Violations::default(),
+            missing_bounds: Violations::default(),
+            not_modular: Violations::default(),
+        };
+        let (objects, hom) = self.checked_hom_sets();
+
+        for &source in &objects {
+            for &target in &objects {
+                let hom_set = hom(source, target);
+                let ends = || (Arc::new(source.clone()), Arc::new(target.clone()));
+                for bound in [M::top(ends().0, ends().1), M::bottom(ends().0, ends().1)] {
+                    if !hom_set.contains(&bound) {
+                        report.missing_bounds.push(bound);
+                    }
+                }
+
+                for left in hom_set {
+                    for right in hom_set {
+                        if !left
+                            .try_meet(right)
+                            .is_some_and(|meet| hom_set.contains(&meet))
+                        {
+                            report.meets_not_closed.push((left.clone(), right.clone()));
+                        }
+                        if !left
+                            .try_join(right)
+                            .is_some_and(|join| hom_set.contains(&join))
+                        {
+                            report.joins_not_closed.push((left.clone(), right.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        for &source in &objects {
+            for &middle in &objects {
+                for first in hom(source, middle) {
+                    let first_dagger = first.dagger();
+                    for &target in &objects {
+                        for second in hom(middle, target) {
+                            let composite = first.compose(second);
+                            for bound in hom(source, target) {
+                                let is_modular = composite
+                                    .try_meet(bound)
+                                    .zip(
+                                        second
+                                            .try_meet(&first_dagger.compose(bound))
+                                            .map(|meet| first.compose(&meet)),
+                                    )
+                                    .is_some_and(|(smaller, bigger)| {
+                                        smaller.is_contained_in(&bigger)
+                                    });
+                                if !is_modular {
+                                    report.not_modular.push((
+                                        first.clone(),
+                                        second.clone(),
+                                        bound.clone(),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+/**
+the source category does not have to be enumerable nor to have a trait for its identities,
+so they are given along with the morphisms to check the laws on
+*/
+pub fn verify_functor<SO, SM, TO, TM, F>(
+    functor: &F,
+    identities: &[SM],
+    morphisms: &[SM],
+) -> FunctorReport<SM>
+where
+    SO: Object,
+    SM: Morphism<SO> + Clone,
+    TO: Object,
+    TM: EndoMorphism<TO>,
+    F: Functor<SO, SM, TO, TM>,
+{
+    let mut report = FunctorReport {
+        misplaced_images: Violations::default(),
+        identities_not_preserved: Violations::default(),
+        composites_not_preserved: Violations::default(),
+    };
+
+    for morphism in morphisms {
+        let image = functor.map_morphism(morphism);
+        if *image.source().borrow() != functor.map_object(morphism.source().borrow())
+            || *image.target().borrow() != functor.map_object(morphism.target().borrow())
+        {
+            report.misplaced_images.push(morphism.clone());
+        }
+    }
+
+    for identity in identities {
+        let image = functor.map_morphism(identity);
+        if image != TM::identity(image.source()) {
+            report.identities_not_preserved.push(identity.clone());
+        }
+    }
+
+    for first in morphisms {
+        for second in morphisms {
+            if let Some(composite) = first.try_compose(second) {
+                if Some(functor.map_morphism(&composite))
+                    != functor
+                        .map_morphism(first)
+                        .try_compose(&functor.map_morphism(second))
+                {
+                    report
+                        .composites_not_preserved
+                        .push((first.clone(), second.clone()));
+                }
+            }
+        }
+    }
+    report
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        category::relation::{CanonModule, Relation},
+        ralg::cgroup::{ideal::CIdeal, C},
+    };
+    use typenum::U4 as N;
+
+    type R = C<N>;
+    type I = CIdeal<N>;
+    type Zn = CanonModule<R, I>;
+
+    #[test]
+    fn relations_form_a_category() {
+        let report = Category::<Zn, Relation<R, I>>::new(1).verify();
+        assert!(report.holds(), "{report}");
+    }
+
+    #[test]
+    fn converse_is_a_dagger() {
+        let report = Category::<Zn, Relation<R, I>>::new(1).verify_dagger();
+        assert!(report.holds(), "{report}");
+    }
+
+    #[test]
+    fn relations_form_an_allegory() {
+        let report = Category::<Zn, Relation<R, I>>::new(1).verify_allegory();
+        assert!(report.holds(), "{report}");
+    }
+
+    #[test]
+    fn broken_hom_sets_are_reported() {
+        let mut category = Category::<Zn, Relation<R, I>>::new(1);
+        let z2: Zn = "Z2".parse().expect("2 divides 4");
+        let endos = category
+            .hom_sets
+            .get_mut(&z2)
+            .and_then(|hom_sets_fixed_source| hom_sets_fixed_source.get_mut(&z2))
+            .expect("Z2 is an object of the category");
+        let identity = Relation::identity(Arc::new(z2.clone()));
+        endos.retain(|endo| *endo != identity);
+        let any_endo = endos
+            .first()
+            .cloned()
+            .expect("there are more endomorphisms");
+        endos.push(any_endo.clone());
+
+        let report = category.verify();
+        assert!(!report.holds());
+        assert_eq!(report.duplicates.count, 1);
+        assert_eq!(report.duplicates.counterexamples, vec![any_endo]);
+        assert_eq!(report.missing_identities.counterexamples, vec![identity]);
+        assert!(report.not_closed.count > 0, "identities are composites");
+        assert_eq!(report.not_unital.count, 0);
+        assert_eq!(report.not_associative.count, 0);
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{
+    category::{
+        morphism::{Enumerable as EnumerableMorphism, Morphism},
+        object::{
+            Duplicable as DuplicableObject, Object,
+            PartiallyEnumerable as PartiallyEnumerableObject,
+        },
+        HomSets,
+    },
+    Int,
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/* # cache */
+
+struct CachedHomSet<M> {
+    morphisms: Arc<[M]>,
+    last_used: u64,
+}
+
+/**
+the clock counts the accesses, so the hom-set with the oldest `last_used`
+is the least recently used one
+*/
+struct Cache<O, M> {
+    hom_sets: HashMap<O, HashMap<O, CachedHomSet<M>>>,
+    clock: u64,
+    nof_morphisms: usize,
+}
+
+impl<O: Object + Hash + Clone, M> Cache<O, M> {
+    fn touch(&mut self, source: &O, target: &O) -> Option<Arc<[M]>> {
+        self.clock = self.clock.wrapping_add(1);
+        let cached = self.hom_sets.get_mut(source)?.get_mut(target)?;
+        cached.last_used = self.clock;
+        Some(Arc::clone(&cached.morphisms))
+    }
+
+    /**
+    the hom-set just inserted is never evicted,
+    so a single hom-set larger than the capacity is still cached
+    */
+    fn insert(&mut self, source: &O, target: &O, morphisms: &Arc<[M]>, capacity: Option<usize>) {
+        // another thread might have generated the same hom-set in the meantime
+        if self.touch(source, target).is_some() {
+            return;
+        }
+        self.nof_morphisms = self.nof_morphisms.saturating_add(morphisms.len());
+        self.hom_sets.entry(source.clone()).or_default().insert(
+            target.clone(),
+            CachedHomSet {
+                morphisms: Arc::clone(morphisms),
+                last_used: self.clock,
+            },
+        );
+
+        while capacity.is_some_and(|limit| self.nof_morphisms > limit) {
+            let Some((lru_source, lru_target)) = self
+                .hom_sets
+                .iter()
+                .flat_map(|(cached_source, hom_sets_fixed_source)| {
+                    hom_sets_fixed_source
+                        .iter()
+                        .filter(move |&(cached_target, _)| {
+                            (cached_source, cached_target) != (source, target)
+                        })
+                        .map(move |(cached_target, cached)| {
+                            (cached.last_used, cached_source, cached_target)
+                        })
+                })
+                .min_by_key(|lru| lru.0)
+                .map(|lru| (lru.1.clone(), lru.2.clone()))
+            else {
+                break;
+            };
+            self.evict(&lru_source, &lru_target);
+        }
+    }
+
+    fn evict(&mut self, source: &O, target: &O) {
+        if let Some(hom_sets_fixed_source) = self.hom_sets.get_mut(source) {
+            if let Some(evicted) = hom_sets_fixed_source.remove(target) {
+                self.nof_morphisms = self.nof_morphisms.saturating_sub(evicted.morphisms.len());
+            }
+            if hom_sets_fixed_source.is_empty() {
+                self.hom_sets.remove(source);
+            }
+        }
+    }
+}
+
+/* # lazy category */
+
+/**
+a category whose hom-sets are generated on the first access and cached afterwards.
+with a capacity, given as the number of morphisms kept in the cache,
+the least recently used hom-sets are evicted once it is exceeded
+and generated again if they are needed later on.
+evicted hom-sets still in use stay alive until they are dropped.
+*/
+pub struct LazyCategory<O: Object, M: Morphism<O>> {
+    sources: HashMap<O, Arc<O>>,
+    targets: HashMap<O, Arc<O>>,
+    capacity: Option<usize>,
+    cache: Mutex<Cache<O, M>>,
+}
+
+impl<
+        O: Object + Hash + Clone + PartiallyEnumerableObject + DuplicableObject,
+        M: Morphism<O, B = Arc<O>> + EnumerableMorphism<O>,
+    > LazyCategory<O, M>
+{
+    pub fn new(maximal_dimension: Int, capacity: Option<usize>) -> Self {
+        Self::from_objects(O::all_by_dimension(0..=maximal_dimension.into()), capacity)
+    }
+
+    pub fn from_objects<J: IntoIterator<Item = O>>(objects: J, capacity: Option<usize>) -> Self {
+        let all_objects: Vec<O> = objects.into_iter().collect();
+
+        // as in `Category::new`, sources and targets do not share their marks
+        Self {
+            sources: all_objects
+                .iter()
+                .map(|object| (object.clone(), Arc::new(object.duplicate())))
+                .collect(),
+            targets: all_objects
+                .into_iter()
+                .map(|object| (object.clone(), Arc::new(object)))
+                .collect(),
+            capacity,
+            cache: Mutex::new(Cache {
+                hom_sets: HashMap::new(),
+                clock: 0,
+                nof_morphisms: 0,
+            }),
+        }
+    }
+}
+
+impl<O: Object + Hash + Clone, M: Morphism<O>> LazyCategory<O, M> {
+    fn cache(&self) -> MutexGuard<'_, Cache<O, M>> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn nof_cached_hom_sets(&self) -> usize {
+        self.cache().hom_sets.values().map(HashMap::len).sum()
+    }
+
+    pub fn nof_cached_morphisms(&self) -> usize {
+        self.cache().nof_morphisms
+    }
+}
+
+impl<
+        O: Object + Hash + Clone + Send + Sync,
+        M: Morphism<O, B = Arc<O>> + EnumerableMorphism<O> + Send + Sync,
+    > HomSets<O, M> for LazyCategory<O, M>
+{
+    type Morphisms<'hom>
+        = Arc<[M]>
+    where
+        Self: 'hom;
+
+    fn objects<'objects>(&'objects self) -> impl Iterator<Item = &'objects O>
+    where
+        O: 'objects,
+    {
+        self.sources.keys()
+    }
+
+    // the cache is not locked while the hom-set is generated, so other threads can use it
+    fn hom(&self, source: &O, target: &O) -> Self::Morphisms<'_> {
+        let cached = self.cache().touch(source, target);
+        if let Some(morphisms) = cached {
+            return morphisms;
+        }
+        let morphisms: Arc<[M]> = M::hom(
+            Arc::clone(
+                self.sources
+                    .get(source)
+                    .expect("source should be an object in the category"),
+            ),
+            Arc::clone(
+                self.targets
+                    .get(target)
+                    .expect("target should be an object in the category"),
+            ),
+        )
+        .collect();
+        self.cache()
+            .insert(source, target, &morphisms, self.capacity);
+        morphisms
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        category::{
+            functors::szymczak::SzymczakClasses,
+            relation::{CanonModule, Relation},
+            Category,
+        },
+        ralg::cgroup::{ideal::CIdeal, C},
+    };
+    use typenum::U4 as N;
+
+    type R = C<N>;
+    type I = CIdeal<N>;
+    type Zn = CanonModule<R, I>;
+
+    #[test]
+    fn hom_sets_agree_with_the_eager_category() {
+        let eager = Category::<Zn, Relation<R, I>>::new(1);
+        let lazy = LazyCategory::<Zn, Relation<R, I>>::new(1, None);
+        assert_eq!(
+            lazy.nof_cached_hom_sets(),
+            0,
+            "nothing is generated up front"
+        );
+
+        for source in eager.objects() {
+            for target in eager.objects() {
+                assert_eq!(*lazy.hom(source, target), *eager.hom(source, target));
+            }
+        }
+        assert_eq!(lazy.nof_cached_hom_sets(), 9);
+    }
+
+    #[test]
+    fn least_recently_used_hom_sets_are_evicted() {
+        let z2: Zn = "Z2".parse().expect("2 divides 4");
+        let z4: Zn = "Z4".parse().expect("4 divides 4");
+        let unbounded = LazyCategory::<Zn, Relation<R, I>>::new(1, None);
+        let capacity = unbounded.hom(&z2, &z2).len() + unbounded.hom(&z4, &z4).len();
+
+        let lazy = LazyCategory::<Zn, Relation<R, I>>::new(1, Some(capacity));
+        lazy.hom(&z2, &z2);
+        lazy.hom(&z4, &z4);
+        assert_eq!(lazy.nof_cached_hom_sets(), 2);
+
+        lazy.hom(&z2, &z2);
+        lazy.hom(&z2, &z4);
+        assert!(lazy.nof_cached_morphisms() <= capacity);
+        assert!(
+            lazy.cache().touch(&z4, &z4).is_none(),
+            "the least recently used hom-set is evicted"
+        );
+        assert!(lazy.cache().touch(&z2, &z2).is_some());
+        assert!(lazy.cache().touch(&z2, &z4).is_some());
+    }
+
+    #[test]
+    fn szymczak_classes_of_a_lazy_category() {
+        // small enough to evict hom-sets all the time
+        let eager = Category::<Zn, Relation<R, I>>::new(1);
+        let lazy = LazyCategory::<Zn, Relation<R, I>>::new(1, Some(10));
+
+        let expected = SzymczakClasses::functor(&eager, 2);
+        let classes = SzymczakClasses::functor(&lazy, 2);
+        assert_eq!(classes.buffer.len(), expected.buffer.len());
+        assert_eq!(
+            classes.number_of_endomorphisms(),
+            expected.number_of_endomorphisms()
+        );
+    }
+}
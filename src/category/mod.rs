@@ -1,22 +1,47 @@
 use crate::{
     category::{
-        morphism::{Enumerable as EnumerableMorphism, Morphism},
+        morphism::{Enumerable as EnumerableMorphism, Morphism, Persistent as PersistentMorphism},
         object::{
+            Classifiable as ClassifiableObject, Concrete as ConcreteObject,
             Duplicable as DuplicableObject, Object,
             PartiallyEnumerable as PartiallyEnumerableObject,
         },
     },
+    util::{
+        checkpoint::{Checkpoint, Persistent},
+        json::{Json, ToJson},
+    },
     Int,
 };
-use std::{collections::HashMap, fmt, hash::Hash, sync::Arc};
+use std::{collections::HashMap, fmt, hash::Hash, io, ops::Deref, sync::Arc};
 
+pub mod arena;
 pub mod functors;
+pub mod graph_relation;
+pub mod laws;
+pub mod lazy;
 pub mod morphism;
 pub mod object;
 pub mod relation;
 
 pub type HomSet<Object, M> = HashMap<Object, HashMap<Object, Vec<M>>>;
 
+/**
+access to the hom-sets of a category,
+whether all of them are generated up front or only once they are asked for.
+the functors share the category between threads, hence `Sync`.
+*/
+pub trait HomSets<O: Object, M: Morphism<O>>: Sync {
+    type Morphisms<'hom>: Deref<Target = [M]>
+    where
+        Self: 'hom;
+
+    fn objects<'objects>(&'objects self) -> impl Iterator<Item = &'objects O>
+    where
+        O: 'objects;
+    fn hom(&self, source: &O, target: &O) -> Self::Morphisms<'_>;
+}
+
 #[derive(Clone)]
 pub struct Category<O: Object, M: Morphism<O>> {
     pub hom_sets: HomSet<O, M>,
@@ -28,7 +53,14 @@ impl<
     > Category<O, M>
 {
     pub fn new(maximal_dimension: Int) -> Self {
-        let all_objects: Vec<O> = O::all_by_dimension(0..=maximal_dimension.into()).collect();
+        Self::from_objects(O::all_by_dimension(0..=maximal_dimension.into()))
+    }
+
+    /**
+    the full subcategory on the given objects
+    */
+    pub fn from_objects<J: IntoIterator<Item = O>>(objects: J) -> Self {
+        let all_objects: Vec<O> = objects.into_iter().collect();
 
         let all_sources: Vec<Arc<O>> = all_objects
             .iter()
@@ -61,6 +93,36 @@ impl<
         Self { hom_sets }
     }
 
+    /**
+    the category with one object of every isomorphism type,
+    together with the number of duplicates left out
+    */
+    pub fn skeleton(maximal_dimension: Int) -> (Self, usize)
+    where
+        O: ClassifiableObject,
+    {
+        let (objects, nof_duplicates) =
+            O::skeleton(O::all_by_dimension(0..=maximal_dimension.into()));
+        (Self::from_objects(objects), nof_duplicates)
+    }
+
+    pub fn with_cardinality(cardinality: usize) -> Self
+    where
+        O: ConcreteObject,
+    {
+        Self::from_objects(
+            O::all_up_to_cardinality(cardinality)
+                .filter(|object| object.cardinality() == cardinality),
+        )
+    }
+
+    pub fn up_to_cardinality(maximal_cardinality: usize) -> Self
+    where
+        O: ConcreteObject,
+    {
+        Self::from_objects(O::all_up_to_cardinality(maximal_cardinality))
+    }
+
     //why cant i use iterator?
     pub fn into_objects(self) -> Vec<O> {
         self.hom_sets.into_keys().collect::<Vec<O>>()
@@ -93,6 +155,154 @@ impl<
     }
 }
 
+impl<O: Object + Hash + Sync, M: Morphism<O> + Sync> HomSets<O, M> for Category<O, M> {
+    type Morphisms<'hom>
+        = &'hom [M]
+    where
+        Self: 'hom;
+
+    fn objects<'objects>(&'objects self) -> impl Iterator<Item = &'objects O>
+    where
+        O: 'objects,
+    {
+        self.hom_sets.keys()
+    }
+
+    fn hom(&self, source: &O, target: &O) -> Self::Morphisms<'_> {
+        self.hom_sets
+            .get(source)
+            .expect("source should be an object in the category")
+            .get(target)
+            .expect("target should be an object in the category")
+    }
+}
+
+/* # checkpoints */
+
+impl<
+        O: Object
+            + Hash
+            + Clone
+            + PartiallyEnumerableObject
+            + DuplicableObject
+            + fmt::Debug
+            + Persistent,
+        M: Morphism<O, B = Arc<O>>
+            + EnumerableMorphism<O>
+            + PersistentMorphism<O>
+            + Clone
+            + fmt::Debug,
+    > Category<O, M>
+{
+    /**
+    the same category as `from_objects`, with the finished hom-sets saved to the checkpoint.
+    when resuming, the hom-sets found there are not generated again.
+    the whole category is saved at the end, so that a later stage can be resumed
+    without generating it once more.
+    */
+    pub fn new_checkpointed<J: IntoIterator<Item = O>>(
+        objects: J,
+        checkpoint: &Checkpoint,
+    ) -> io::Result<Self> {
+        let all_objects: Vec<O> = objects.into_iter().collect();
+
+        let all_sources: Vec<Arc<O>> = all_objects
+            .iter()
+            .map(|object| Arc::new(object.duplicate()))
+            .collect();
+
+        let all_targets: Vec<Arc<O>> = all_objects
+            .into_iter()
+            .map(|object| Arc::new(object))
+            .collect();
+
+        let mut hom_sets: HomSet<O, M> =
+            checkpoint.load(Self::restore_hom_sets)?.unwrap_or_default();
+        let mut generated_any = false;
+
+        for source in &all_sources {
+            for target in &all_targets {
+                if hom_sets
+                    .get(source.as_ref())
+                    .is_some_and(|hom_sets_fixed_source| {
+                        hom_sets_fixed_source.contains_key(target.as_ref())
+                    })
+                {
+                    continue;
+                }
+                let hom_set_fixed_source_and_target =
+                    M::hom(Arc::clone(source), Arc::clone(target)).collect();
+                hom_sets
+                    .entry(source.as_ref().clone())
+                    .or_default()
+                    .insert(target.as_ref().clone(), hom_set_fixed_source_and_target);
+                generated_any = true;
+
+                checkpoint.save_if_due(|bytes| Self::persist_hom_sets(&hom_sets, bytes));
+            }
+        }
+
+        if generated_any {
+            checkpoint.save(|bytes| Self::persist_hom_sets(&hom_sets, bytes))?;
+        }
+        Ok(Self { hom_sets })
+    }
+
+    fn persist_hom_sets(hom_sets: &HomSet<O, M>, bytes: &mut Vec<u8>) {
+        hom_sets
+            .values()
+            .map(HashMap::len)
+            .sum::<usize>()
+            .persist(bytes);
+        for (source, hom_sets_fixed_source) in hom_sets {
+            for (target, morphisms) in hom_sets_fixed_source {
+                source.persist(bytes);
+                target.persist(bytes);
+                morphisms.len().persist(bytes);
+                for morphism in morphisms {
+                    morphism.persist(bytes);
+                }
+            }
+        }
+    }
+
+    // as in `new`, every object is shared by the morphisms, once as a source and once as a target
+    fn restore_hom_sets(bytes: &mut &[u8]) -> Option<HomSet<O, M>> {
+        let mut sources: HashMap<O, Arc<O>> = HashMap::new();
+        let mut targets: HashMap<O, Arc<O>> = HashMap::new();
+        let mut hom_sets = HomSet::<O, M>::new();
+
+        for _ in 0..usize::restore(bytes)? {
+            let source = O::restore(bytes)?;
+            let target = O::restore(bytes)?;
+            let shared_source = Arc::clone(
+                sources
+                    .entry(source.clone())
+                    .or_insert_with(|| Arc::new(source.duplicate())),
+            );
+            let shared_target = Arc::clone(
+                targets
+                    .entry(target.clone())
+                    .or_insert_with(|| Arc::new(target.clone())),
+            );
+
+            let mut morphisms = Vec::new();
+            for _ in 0..usize::restore(bytes)? {
+                morphisms.push(M::restore(
+                    Arc::clone(&shared_source),
+                    Arc::clone(&shared_target),
+                    bytes,
+                )?);
+            }
+            hom_sets
+                .entry(source)
+                .or_default()
+                .insert(target, morphisms);
+        }
+        Some(hom_sets)
+    }
+}
+
 impl<O: Object + fmt::Debug, M: Morphism<O> + fmt::Debug> fmt::Debug for Category<O, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut string = String::new();
@@ -119,6 +329,85 @@ impl<O: Object + fmt::Debug, M: Morphism<O> + fmt::Debug> fmt::Debug for Categor
     }
 }
 
+/**
+a summary of the category: the number of morphisms in every hom-set.
+the lines are sorted, since the order of the hash maps is not stable between runs.
+*/
+impl<O: Object + fmt::Display + PrettyName, M: Morphism<O> + PrettyName> fmt::Display
+    for Category<O, M>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut hom_sets: Vec<String> = self
+            .hom_sets
+            .iter()
+            .flat_map(|(source, hom_sets_fixed_object)| {
+                hom_sets_fixed_object
+                    .iter()
+                    .map(move |(target, morphisms)| {
+                        format!("{source} -> {target}: {}\n", morphisms.len())
+                    })
+            })
+            .collect();
+        hom_sets.sort();
+
+        let nof_morphisms: usize = self
+            .hom_sets
+            .values()
+            .flat_map(HashMap::values)
+            .map(Vec::len)
+            .sum();
+
+        write!(
+            f,
+            "Object: {}\nMorphism: {}\nNumber of objects: {}\nNumber of morphisms: {nof_morphisms}\n===\n{}",
+            O::PRETTY_NAME,
+            M::PRETTY_NAME,
+            self.hom_sets.len(),
+            hom_sets.concat()
+        )
+    }
+}
+
+/**
+the hom-sets are sorted by their source and target,
+since the order of the hash maps is not stable between runs.
+*/
+impl<O: Object + ToJson + PrettyName, M: Morphism<O> + ToJson + PrettyName> ToJson
+    for Category<O, M>
+{
+    fn to_json(&self) -> Json {
+        let mut hom_sets: Vec<(String, String, Json)> = self
+            .hom_sets
+            .iter()
+            .flat_map(|(source, hom_sets_fixed_object)| {
+                hom_sets_fixed_object
+                    .iter()
+                    .map(move |(target, morphisms)| {
+                        (
+                            source.to_json().to_string(),
+                            target.to_json().to_string(),
+                            Json::object([
+                                ("source", source.to_json()),
+                                ("target", target.to_json()),
+                                ("morphisms", morphisms.to_json()),
+                            ]),
+                        )
+                    })
+            })
+            .collect();
+        hom_sets.sort_by(|left, right| (&left.0, &left.1).cmp(&(&right.0, &right.1)));
+
+        Json::object([
+            ("object", O::PRETTY_NAME.to_json()),
+            ("morphism", M::PRETTY_NAME.to_json()),
+            (
+                "hom_sets",
+                Json::Array(hom_sets.into_iter().map(|hom_set| hom_set.2).collect()),
+            ),
+        ])
+    }
+}
+
 pub trait PrettyName {
     const PRETTY_NAME: &'static str;
 }
@@ -140,4 +429,92 @@ mod test {
 
         assert_eq!(category.into_objects().len(), 2);
     }
+
+    #[test]
+    fn subcategories() {
+        use typenum::U8 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+        type Zn = CanonModule<R, I>;
+        let module = |name: &str| name.parse::<Zn>().expect("the module is over Z8");
+
+        let chosen =
+            Category::<Zn, Relation<R, I>>::from_objects(["Z2xZ4", "Z8", "Z2xZ2xZ2"].map(module));
+        assert_eq!(chosen.hom_sets.len(), 3);
+        assert_eq!(
+            chosen.hom(&module("Z8"), &module("Z2xZ4")).len(),
+            Category::<Zn, Relation<R, I>>::from_objects([module("Z8"), module("Z2xZ4")])
+                .hom(&module("Z8"), &module("Z2xZ4"))
+                .len(),
+            "hom-sets do not depend on the other objects"
+        );
+
+        // Z8, Z2xZ4, Z2xZ2xZ2, and no module of dimension 4 is needed to find them
+        let order_8 = Category::<Zn, Relation<R, I>>::with_cardinality(8);
+        assert_eq!(order_8.hom_sets.len(), 3);
+        assert!(order_8.objects().all(|object| object.cardinality() == 8));
+
+        // 0, Z2, Z4, Z2xZ2
+        let up_to_order_4 = Category::<Zn, Relation<R, I>>::up_to_cardinality(4);
+        assert_eq!(up_to_order_4.hom_sets.len(), 4);
+    }
+
+    #[test]
+    fn skeleton() {
+        use typenum::U6 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+        type Zn = CanonModule<R, I>;
+
+        // both Z2xZ3 and Z3xZ2 are enumerated
+        let (skeleton, nof_duplicates) = Category::<Zn, Relation<R, I>>::skeleton(2);
+        assert_eq!(nof_duplicates, 1);
+        assert_eq!(skeleton.hom_sets.len(), 1 + 2 + 3);
+        assert_eq!(
+            skeleton.hom_sets,
+            Category::<Zn, Relation<R, I>>::new(2).hom_sets,
+            "duplicates are equal objects"
+        );
+    }
+
+    #[test]
+    fn resuming_category() {
+        use std::time::Duration;
+        use typenum::U4 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+        type Zn = CanonModule<R, I>;
+
+        let expected = Category::<Zn, Relation<R, I>>::new(1);
+
+        // a run killed before generating the hom-sets from one of the objects
+        let mut partial = expected.hom_sets.clone();
+        let missing: Zn = "Z2".parse().expect("2 divides 4");
+        partial.remove(&missing);
+
+        let path = std::env::temp_dir().join(format!(
+            "szymczak-category-{}.checkpoint",
+            std::process::id()
+        ));
+        let checkpoint = Checkpoint::new(path, "Z4 dim 1".to_owned(), Duration::ZERO, true);
+        checkpoint
+            .save(|bytes| Category::persist_hom_sets(&partial, bytes))
+            .expect("temporary directory is writable");
+
+        let resumed = Category::<Zn, Relation<R, I>>::new_checkpointed(
+            Zn::all_by_dimension(0..=1),
+            &checkpoint,
+        )
+        .expect("checkpoint is readable");
+        assert_eq!(resumed.hom_sets, expected.hom_sets);
+
+        let restored = Category::<Zn, Relation<R, I>>::new_checkpointed(
+            Zn::all_by_dimension(0..=1),
+            &checkpoint,
+        )
+        .expect("checkpoint is readable");
+        assert_eq!(restored.hom_sets, expected.hom_sets);
+
+        checkpoint.remove().expect("checkpoint is removable");
+    }
 }
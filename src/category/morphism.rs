@@ -26,7 +26,7 @@ pub trait Morphism<O: Object>: Sized {
 
     fn compose(&self, other: &Self) -> Self;
     fn try_compose(&self, other: &Self) -> Option<Self> {
-        (self.target().borrow() == other.source().borrow()).then_some(self.compose(other))
+        (self.target().borrow() == other.source().borrow()).then(|| self.compose(other))
     }
 }
 
@@ -58,7 +58,7 @@ pub trait Endo<O: Object>: Morphism<O> + Clone + Eq + Hash {
 
     fn try_cycle(&self) -> Option<Vec<Self>> {
         // nie ma potrzeby trzymać całego morfizmu, wystarczy perfekcyjny hash
-        (self.source().borrow() == self.target().borrow()).then_some({
+        (self.source().borrow() == self.target().borrow()).then(|| {
             let mut seen_iterations = HashSet::new();
 
             seen_iterations.insert(Self::identity(self.source()));
@@ -119,6 +119,135 @@ pub trait IsBij<O: Object>: Morphism<O> {
     fn is_a_bijection(&self) -> bool;
 }
 
+/**
+an involutive contravariant endofunctor which is the identity on the objects:
+the dagger of `f: a -> b` is `f^dagger: b -> a`,
+`(f g)^dagger = g^dagger f^dagger` and `f^dagger^dagger = f`
+*/
+pub trait Dagger<O: Object>: Morphism<O> {
+    fn dagger(&self) -> Self;
+
+    // for relations these are the symmetric endorelations
+    fn is_self_dagger(&self) -> bool
+    where
+        Self: PartialEq,
+    {
+        self.source().borrow() == self.target().borrow() && self.dagger() == *self
+    }
+}
+
+/**
+in an allegory every hom-set is a meet-semilattice.
+the hom-sets considered here are lattices with the least and the greatest element,
+so meets and joins are defined exactly for the morphisms of the same hom-set
+*/
+pub trait Allegory<O: Object>: Dagger<O> {
+    fn try_meet(&self, other: &Self) -> Option<Self>;
+    fn try_join(&self, other: &Self) -> Option<Self>;
+    fn is_contained_in(&self, other: &Self) -> bool;
+
+    fn top(source: Self::B, target: Self::B) -> Self;
+    fn bottom(source: Self::B, target: Self::B) -> Self;
+
+    fn is_parallel_to(&self, other: &Self) -> bool {
+        self.source().borrow() == other.source().borrow()
+            && self.target().borrow() == other.target().borrow()
+    }
+}
+
+/**
+the properties of endomorphisms of an allegory, named after those of endorelations.
+all of them are stated with the composition, the dagger and the inclusion only,
+so a morphism which is not an endomorphism has none of them, nor any closures
+*/
+pub trait Endorelation<O: Object>: Allegory<O> + Endo<O> {
+    fn is_an_endo(&self) -> bool {
+        self.source().borrow() == self.target().borrow()
+    }
+
+    fn is_reflexive(&self) -> bool {
+        self.is_an_endo() && Self::identity(self.source()).is_contained_in(self)
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.is_self_dagger()
+    }
+
+    fn is_transitive(&self) -> bool {
+        self.is_an_endo() && self.compose(self).is_contained_in(self)
+    }
+
+    fn is_an_equivalence(&self) -> bool {
+        self.is_reflexive() && self.is_symmetric() && self.is_transitive()
+    }
+
+    fn is_a_partial_equivalence(&self) -> bool {
+        self.is_symmetric() && self.is_transitive()
+    }
+
+    fn is_difunctional(&self) -> bool {
+        self.is_an_endo()
+            && self
+                .compose(&self.dagger())
+                .compose(self)
+                .is_contained_in(self)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.is_an_endo() && self.compose(self) == *self
+    }
+
+    fn try_reflexive_closure(&self) -> Option<Self> {
+        self.is_an_endo()
+            .then(|| self.try_join(&Self::identity(self.source())))
+            .flatten()
+    }
+
+    /**
+    the least transitive morphism containing this one. for additive relations
+    the join is the sum of the submodules, which may be larger than the union of the powers,
+    so the closure is taken under both the join and the composition until it is stable
+    */
+    fn try_transitive_closure(&self) -> Option<Self> {
+        self.is_an_endo()
+            .then(|| closure(self.clone(), |current| current.compose(current)))
+            .flatten()
+    }
+
+    fn try_difunctional_closure(&self) -> Option<Self> {
+        self.is_an_endo()
+            .then(|| {
+                closure(self.clone(), |current| {
+                    current.compose(&current.dagger()).compose(current)
+                })
+            })
+            .flatten()
+    }
+}
+
+/**
+joins the morphism with its image under the step until nothing new is added
+*/
+fn closure<O: Object, M: Allegory<O> + Eq, F: Fn(&M) -> M>(morphism: M, step: F) -> Option<M> {
+    let mut current = morphism;
+    loop {
+        let next = current.try_join(&step(&current))?;
+        if next == current {
+            return Some(current);
+        }
+        current = next;
+    }
+}
+
+/**
+morphisms are stored in the checkpoints next to their source and target,
+so only what tells them apart within a hom-set is persisted
+*/
+pub trait Persistent<O: Object>: Morphism<O> {
+    fn persist(&self, bytes: &mut Vec<u8>);
+    fn restore(source: Self::B, target: Self::B, bytes: &mut &[u8]) -> Option<Self>;
+}
+
 /*
 pub trait AbelianEndoMorphism<R: Ring, Object: Module<R> + Eq>:
     EndoMorphism<Object> + AbelianMorphism<R, Object, Object>
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, ops};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    ops,
+};
 
 pub trait Object: Sized + PartialEq + Eq {}
 
@@ -19,6 +23,20 @@ pub trait PartiallyEnumerable: Object {
             .map(|d| (d, Self::all_fixed_dimension(d).collect()))
             .collect()
     }
+
+    /**
+    every summand of an enumerated object is nontrivial,
+    so an object of dimension d has at least 2^d elements
+    and only the dimensions up to the binary logarithm of the bound are searched
+    */
+    fn all_up_to_cardinality(maximal_cardinality: usize) -> impl Iterator<Item = Self>
+    where
+        Self: Concrete,
+    {
+        let maximal_dimension = maximal_cardinality.checked_ilog2().unwrap_or(0);
+        Self::all_by_dimension(0..=maximal_dimension.try_into().expect("u32 fits in usize"))
+            .filter(move |object| object.cardinality() <= maximal_cardinality)
+    }
 }
 
 pub trait Enumerable: Object {
@@ -39,3 +57,30 @@ pub trait Concrete: Object {
 pub trait Duplicable: Object {
     fn duplicate(&self) -> Self;
 }
+
+/**
+objects with a complete invariant of their isomorphism type
+*/
+pub trait Classifiable: Object {
+    type IsomorphismType: Hash + Eq;
+
+    fn isomorphism_type(&self) -> Self::IsomorphismType;
+
+    /**
+    the first object of every isomorphism type, in the given order,
+    and the number of the other ones left out
+    */
+    fn skeleton<J: IntoIterator<Item = Self>>(objects: J) -> (Vec<Self>, usize) {
+        let mut isomorphism_types = HashSet::new();
+        let mut representatives = Vec::new();
+        let mut nof_duplicates: usize = 0;
+        for object in objects {
+            if isomorphism_types.insert(object.isomorphism_type()) {
+                representatives.push(object);
+            } else {
+                nof_duplicates = nof_duplicates.saturating_add(1);
+            }
+        }
+        (representatives, nof_duplicates)
+    }
+}
@@ -1,8 +1,9 @@
 pub use crate::{
     category::{
         morphism::{
-            Concrete as ConcreteMorphism, Endo as EndoMorphism, Enumerable as EnumerableMorphism,
-            IsBij, IsMap, IsMatching, IsWide, Morphism,
+            Allegory, Concrete as ConcreteMorphism, Dagger, Endo as EndoMorphism, Endorelation,
+            Enumerable as EnumerableMorphism, IsBij, IsMap, IsMatching, IsWide, Morphism,
+            Persistent as PersistentMorphism,
         },
         object::Concrete as ConcreteObject,
         PrettyName,
@@ -11,23 +12,32 @@ pub use crate::{
         cgroup::{ideal::CIdeal, Radix, C},
         matrix::Matrix,
         module::{
-            canon::object::Object as CanonModule, direct::Object as DirectModule, map::CanonToCanon,
+            canon::{element::Element as CanonElement, object::Object as CanonModule},
+            direct::Object as DirectModule,
+            map::CanonToCanon,
         },
         ring::{
             ideal::{Ideal, Principal as PrincipalIdeal},
-            Ring,
+            AdditivePartialMonoid, Ring,
         },
     },
 };
 
-use std::{fmt, hash, sync::Arc};
+use crate::util::{
+    bitmatrix::BitMatrix,
+    checkpoint::Persistent,
+    json::{Json, ToJson},
+};
+use bitvec::vec::BitVec;
+use itertools::Itertools;
+use std::{collections::HashMap, fmt, hash, sync::Arc};
 use typenum::{IsGreater, U1};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Relation<R: Ring, I: Ideal<Parent = R> + Ord> {
     pub source: Arc<CanonModule<R, I>>,
     pub target: Arc<CanonModule<R, I>>,
-    pub matrix: Matrix<bool>,
+    pub matrix: BitMatrix,
 }
 
 impl<R: Ring + fmt::Debug, I: Ideal<Parent = R> + Ord + fmt::Debug> fmt::Debug for Relation<R, I> {
@@ -86,6 +96,44 @@ where
     }
 }
 
+/**
+one string of bits per element of the source, as in the debug representation
+*/
+impl<R: Ring, I: Ideal<Parent = R> + Ord> ToJson for Relation<R, I>
+where
+    CanonModule<R, I>: ToJson,
+{
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("source", self.source.to_json()),
+            ("target", self.target.to_json()),
+            (
+                "matrix",
+                Json::Array(
+                    (0..self.matrix.nof_cols)
+                        .map(|ind_col| {
+                            Json::String(
+                                (0..self.matrix.nof_rows)
+                                    .map(|ind_row| {
+                                        match self
+                                            .matrix
+                                            .get(ind_col, ind_row)
+                                            .expect("the indices are in proper bounds")
+                                        {
+                                            true => '1',
+                                            false => '0',
+                                        }
+                                    })
+                                    .collect(),
+                            )
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
 impl<R: Ring, I: Ideal<Parent = R> + Ord> PrettyName for Relation<R, I> {
     const PRETTY_NAME: &'static str = "Relation";
 }
@@ -107,7 +155,7 @@ impl<R: Ring, I: Ideal<Parent = R> + Ord> Morphism<CanonModule<R, I>> for Relati
         Self {
             source: Arc::clone(&self.source),
             target: Arc::clone(&other.target),
-            matrix: self.matrix.compose_unchecked_bool(&other.matrix),
+            matrix: self.matrix.compose(&other.matrix),
         }
     }
 }
@@ -123,7 +171,7 @@ impl<R: Ring + Copy + hash::Hash, I: Ideal<Parent = R> + Ord + hash::Hash>
         Self {
             source: Arc::clone(&object),
             target: Arc::clone(&object),
-            matrix: Matrix::<bool>::from_buffer(buffer, card, card),
+            matrix: BitMatrix::from_buffer(buffer, card, card),
         }
     }
 }
@@ -219,7 +267,7 @@ impl<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>
         Self {
             source: direct.left(),
             target: direct.right(),
-            matrix: Matrix::from_buffer(buffer, cols.into(), rows.into()),
+            matrix: BitMatrix::from_buffer(buffer, cols.into(), rows.into()),
         }
     }
 }
@@ -239,47 +287,708 @@ impl<Period: Radix + IsGreater<U1> + Send + Sync>
     }
 }
 
+/* # lambek invariants */
+
+/**
+the invariants of an additive relation `r: a -> b`, given as the inclusions into `a` and `b`,
+together with the isomorphism `domain / kernel -> image / indeterminacy` induced by `r`:
+* `domain`, the elements of `a` related to anything,
+* `kernel`, the elements of `a` related to zero,
+* `image`, the elements of `b` to which anything is related,
+* `indeterminacy`, the elements of `b` to which zero is related.
+*/
+#[derive(Clone, Debug)]
+pub struct LambekInvariants<R: Ring, I: Ideal<Parent = R> + Ord> {
+    pub domain: CanonToCanon<R, I>,
+    pub kernel: CanonToCanon<R, I>,
+    pub image: CanonToCanon<R, I>,
+    pub indeterminacy: CanonToCanon<R, I>,
+    pub isomorphism: CanonToCanon<R, I>,
+}
+
+/**
+the unique map `g` such that `g mono = map`, found by searching the source of `mono`.
+`mono` should be mono and the image of `map` should be contained in its image
+*/
+#[allow(clippy::expect_used, reason = "structural properties")]
+fn lift<R: Ring + Copy, I: Ideal<Parent = R> + Ord>(
+    map: &CanonToCanon<R, I>,
+    mono: &CanonToCanon<R, I>,
+) -> CanonToCanon<R, I> {
+    let source = map.source();
+    let cols: Vec<Vec<R>> = source
+        .iter()
+        .map(|mark| {
+            let value = map
+                .try_evaluate(source.versor(mark))
+                .expect("versor is an element of the source");
+            mono.source()
+                .elements()
+                .find(|element| {
+                    mono.try_evaluate(element.clone())
+                        .and_then(|image| image.is_equal(&value))
+                        == Some(true)
+                })
+                .expect("the image of map should be contained in the image of mono")
+                .into_values()
+                .collect()
+        })
+        .collect();
+    CanonToCanon::new(
+        &source,
+        &mono.source(),
+        Matrix::from_cols_custom(cols, source.dimension(), mono.source().dimension()),
+    )
+}
+
+/**
+the unique map `g` such that `epi g = map`, found by searching the source of `epi`.
+`epi` should be epi and `map` should vanish on its kernel
+*/
+#[allow(clippy::expect_used, reason = "structural properties")]
+fn descend<R: Ring + Copy, I: Ideal<Parent = R> + Ord>(
+    map: &CanonToCanon<R, I>,
+    epi: &CanonToCanon<R, I>,
+) -> CanonToCanon<R, I> {
+    let target = epi.target();
+    let cols: Vec<Vec<R>> = target
+        .iter()
+        .map(|mark| {
+            let versor = target.versor(mark);
+            let preimage = epi
+                .source()
+                .elements()
+                .find(|element| {
+                    epi.try_evaluate(element.clone())
+                        .and_then(|image| image.is_equal(&versor))
+                        == Some(true)
+                })
+                .expect("epi should be surjective");
+            map.try_evaluate(preimage)
+                .expect("preimage is an element of the source")
+                .into_values()
+                .collect()
+        })
+        .collect();
+    CanonToCanon::new(
+        &target,
+        &map.target(),
+        Matrix::from_cols_custom(cols, target.dimension(), map.target().dimension()),
+    )
+}
+
+impl<Period: Radix + IsGreater<U1> + Send + Sync> Relation<C<Period>, CIdeal<Period>> {
+    /**
+    the relation as the inclusion of a submodule of the direct sum of its source and target,
+    `DirectModule::sumproduct(source, target)`, generated by the related pairs.
+    every pair is sent to by its own copy of `Z_n`, split into the summands `Z_q` as usual,
+    the versor of `Z_q` going to `(n / q) (a, b)`, and these generate the multiples of `(a, b)`
+    */
+    #[allow(clippy::expect_used, reason = "every relation comes from a submodule")]
+    pub fn submodule(&self) -> CanonToCanon<C<Period>, CIdeal<Period>> {
+        use crate::category::morphism::PreAbelian;
+        type Cols<Period> = Vec<Vec<C<Period>>>;
+
+        let direct = DirectModule::sumproduct(&self.source, &self.target);
+        let pairs: Vec<(usize, usize)> = (0..self.matrix.nof_rows)
+            .flat_map(|row| (0..self.matrix.nof_cols).map(move |col| (col, row)))
+            .filter(|&(col, row)| self.matrix.get(col, row) == Some(true))
+            .collect();
+        let free: Arc<CanonModule<_, _>> =
+            Arc::new(pairs.iter().map(|_pair| Period::U16).collect());
+        let (source_elements, target_elements) = (
+            indexed_elements(&self.source),
+            indexed_elements(&self.target),
+        );
+        let (source_tc, target_tc): (Vec<u16>, Vec<u16>) = (
+            self.source.torsion_coeffs_as_u16().collect(),
+            self.target.torsion_coeffs_as_u16().collect(),
+        );
+        // the entries are reduced, since the maps are computed over the whole ring
+        let multiple = |element: &CanonElement<C<Period>, CIdeal<Period>>,
+                        torsion_coeffs: &[u16],
+                        scalar: u16| {
+            element
+                .clone()
+                .into_values()
+                .zip(torsion_coeffs)
+                .map(|(value, &coeff)| {
+                    C::from(
+                        u16::from(value)
+                            .saturating_mul(scalar)
+                            .checked_rem(coeff)
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect()
+        };
+
+        // the summands `Z_q` of every copy of `Z_n`, in the order of the marks of the free module
+        let mut nof_summands = HashMap::<u16, usize>::new();
+        let (source_cols, target_cols): (Cols<Period>, Cols<Period>) = free
+            .torsion_coeffs_as_u16()
+            .map(|coeff| {
+                let copy = nof_summands.entry(coeff).or_default();
+                let &(col, row) = pairs
+                    .get(*copy)
+                    .expect("every copy has a summand of every order");
+                *copy = copy.saturating_add(1);
+                let scalar = Period::U16.checked_div(coeff).unwrap_or_default();
+                (
+                    multiple(
+                        source_elements
+                            .get(col)
+                            .expect("index calculated to be within range"),
+                        &source_tc,
+                        scalar,
+                    ),
+                    multiple(
+                        target_elements
+                            .get(row)
+                            .expect("index calculated to be within range"),
+                        &target_tc,
+                        scalar,
+                    ),
+                )
+            })
+            .unzip();
+
+        let to_source = CanonToCanon::new(
+            &free,
+            &self.source,
+            Matrix::from_cols_custom(source_cols, free.dimension(), self.source.dimension()),
+        );
+        let to_target = CanonToCanon::new(
+            &free,
+            &self.target,
+            Matrix::from_cols_custom(target_cols, free.dimension(), self.target.dimension()),
+        );
+        PreAbelian::image(
+            &to_source
+                .compose(&direct.left_inclusion)
+                .try_add(to_target.compose(&direct.right_inclusion))
+                .expect("both legs go from the free module to the direct sum"),
+        )
+    }
+
+    /**
+    the jointly monic span `a <- s -> b` whose legs `f` and `g` are the projections
+    restricted to the submodule `s`, so that the relation is `f^dagger g`
+    */
+    pub fn tabulate(&self) -> Span<C<Period>, CIdeal<Period>> {
+        let direct = DirectModule::sumproduct(&self.source, &self.target);
+        let submodule = self.submodule();
+        (
+            submodule.compose(&direct.left_projection),
+            submodule.compose(&direct.right_projection),
+        )
+    }
+
+    pub fn lambek_invariants(&self) -> LambekInvariants<C<Period>, CIdeal<Period>> {
+        use crate::category::morphism::PreAbelian;
+
+        let direct = DirectModule::sumproduct(&self.source, &self.target);
+        let submodule = self.submodule();
+        let to_source = submodule.compose(&direct.left_projection);
+        let to_target = submodule.compose(&direct.right_projection);
+
+        let domain = PreAbelian::image(&to_source);
+        let image = PreAbelian::image(&to_target);
+        let kernel = to_target.kernel().compose(&to_source);
+        let indeterminacy = to_source.kernel().compose(&to_target);
+
+        let domain_quotient = lift(&kernel, &domain).cokernel();
+        let image_quotient = lift(&indeterminacy, &image).cokernel();
+        let isomorphism = descend(
+            &lift(&to_target, &image).compose(&image_quotient),
+            &lift(&to_source, &domain).compose(&domain_quotient),
+        );
+
+        LambekInvariants {
+            domain,
+            kernel,
+            image,
+            indeterminacy,
+            isomorphism,
+        }
+    }
+}
+
+/* # spans and cospans */
+
+/**
+a span `a <- s -> b` given by its legs
+*/
+#[allow(type_alias_bounds, reason = "waiting on feature `lazy_type_alias`")]
+pub type Span<R: Ring, I: Ideal<Parent = R> + Ord> = (CanonToCanon<R, I>, CanonToCanon<R, I>);
+
+/**
+the position of the element in the source or the target of a relation,
+in mixed radix as in the conversion from submodules
+*/
+#[allow(
+    clippy::arithmetic_side_effects,
+    reason = "the indices are bounded by the cardinality of the module"
+)]
+fn element_index<R: Ring + Into<u16>, I: Ideal<Parent = R> + Ord>(
+    torsion_coeffs: &[u16],
+    element: CanonElement<R, I>,
+) -> usize {
+    element
+        .into_values()
+        .collect::<Vec<R>>()
+        .into_iter()
+        .zip(torsion_coeffs)
+        .rev()
+        .fold(0, |index, (value, &coeff)| {
+            index * usize::from(coeff) + usize::from(value.into() % coeff)
+        })
+}
+
+/**
+the elements of the module at their positions
+*/
+#[allow(
+    clippy::expect_used,
+    reason = "every element has an index below the cardinality"
+)]
+fn indexed_elements<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>(
+    module: &CanonModule<R, I>,
+) -> Vec<CanonElement<R, I>> {
+    let torsion_coeffs: Vec<u16> = module.torsion_coeffs_as_u16().collect();
+    let mut elements = vec![None; module.cardinality()];
+    for element in module.elements() {
+        let index = element_index(&torsion_coeffs, element.clone());
+        *elements
+            .get_mut(index)
+            .expect("index calculated to be within range") = Some(element);
+    }
+    elements
+        .into_iter()
+        .map(|element| element.expect("every index is taken by an element"))
+        .collect()
+}
+
+impl<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord> Relation<R, I> {
+    fn from_pairs<J: IntoIterator<Item = (CanonElement<R, I>, CanonElement<R, I>)>>(
+        source: Arc<CanonModule<R, I>>,
+        target: Arc<CanonModule<R, I>>,
+        pairs: J,
+    ) -> Self {
+        let source_tc: Vec<u16> = source.torsion_coeffs_as_u16().collect();
+        let target_tc: Vec<u16> = target.torsion_coeffs_as_u16().collect();
+        let (cols, rows) = (source.cardinality(), target.cardinality());
+
+        let mut buffer = vec![false; cols.saturating_mul(rows)];
+        for (source_element, target_element) in pairs {
+            let index = element_index(&source_tc, source_element)
+                .saturating_add(cols.saturating_mul(element_index(&target_tc, target_element)));
+            *buffer
+                .get_mut(index)
+                .expect("index calculated to be within range") = true;
+        }
+
+        Self {
+            source,
+            target,
+            matrix: BitMatrix::from_buffer(buffer, cols, rows),
+        }
+    }
+
+    /**
+    the graph of the map, the relation between each element and its image
+    */
+    #[allow(clippy::expect_used, reason = "the elements are taken from the source")]
+    pub fn graph(map: &CanonToCanon<R, I>) -> Self {
+        Self::from_pairs(
+            map.source(),
+            map.target(),
+            map.source().elements().map(|element| {
+                (
+                    element.clone(),
+                    map.try_evaluate(element).expect("element of the source"),
+                )
+            }),
+        )
+    }
+
+    /**
+    the map whose graph is the relation, if it is a map
+    */
+    #[allow(
+        clippy::expect_used,
+        reason = "a map relates every element to some element"
+    )]
+    pub fn try_into_map(&self) -> Option<CanonToCanon<R, I>> {
+        let source_tc: Vec<u16> = self.source.torsion_coeffs_as_u16().collect();
+        let target_tc: Vec<u16> = self.target.torsion_coeffs_as_u16().collect();
+        self.is_a_map().then(|| {
+            let cols: Vec<Vec<R>> = self
+                .source
+                .iter()
+                .map(|mark| {
+                    let col = element_index(&source_tc, self.source.versor(mark));
+                    let row = (0..self.matrix.nof_rows)
+                        .find(|&row| self.matrix.get(col, row) == Some(true))
+                        .expect("the versor is related to some element");
+                    self.target
+                        .elements()
+                        .find(|element| element_index(&target_tc, element.clone()) == row)
+                        .expect("the index is in proper bounds")
+                        .into_values()
+                        .collect()
+                })
+                .collect();
+            CanonToCanon::new(
+                &self.source,
+                &self.target,
+                Matrix::from_cols_custom(cols, self.source.dimension(), self.target.dimension()),
+            )
+        })
+    }
+
+    /**
+    the image of `s -> a + b` for the span `a <- s -> b` given by its legs,
+    if they have the same source
+    */
+    #[allow(
+        clippy::expect_used,
+        reason = "the elements are taken from the sources"
+    )]
+    pub fn try_from_span(left: &CanonToCanon<R, I>, right: &CanonToCanon<R, I>) -> Option<Self> {
+        (left.source() == right.source()).then(|| {
+            Self::from_pairs(
+                left.target(),
+                right.target(),
+                left.source().elements().map(|element| {
+                    (
+                        left.try_evaluate(element.clone())
+                            .expect("element of the source"),
+                        right.try_evaluate(element).expect("element of the source"),
+                    )
+                }),
+            )
+        })
+    }
+
+    /**
+    the pullback of `a -> t <- b` for the cospan given by its legs,
+    if they have the same target
+    */
+    #[allow(
+        clippy::expect_used,
+        reason = "the elements are taken from the sources"
+    )]
+    pub fn try_from_cospan(left: &CanonToCanon<R, I>, right: &CanonToCanon<R, I>) -> Option<Self> {
+        (left.target() == right.target()).then(|| {
+            let left_source = left.source();
+            let right_source = right.source();
+            Self::from_pairs(
+                left.source(),
+                right.source(),
+                left_source
+                    .elements()
+                    .cartesian_product(right_source.elements())
+                    .filter(|pair| {
+                        left.try_evaluate(pair.0.clone())
+                            .expect("element of the source")
+                            .is_equal(
+                                &right
+                                    .try_evaluate(pair.1.clone())
+                                    .expect("element of the source"),
+                            )
+                            == Some(true)
+                    }),
+            )
+        })
+    }
+
+    /**
+    the related pairs of elements, as in `{(0) ~ (0), (1) ~ (2)}`
+    */
+    pub const fn pairs(&self) -> Pairs<'_, R, I> {
+        Pairs(self)
+    }
+
+    /**
+    the matrix with the elements of the source labelling the rows
+    and the elements of the target labelling the columns
+    */
+    pub const fn grid(&self) -> Grid<'_, R, I> {
+        Grid(self)
+    }
+}
+
+/* # element-level display */
+
+/**
+the labels of the elements of the module, in the order of the matrix of a relation.
+an element is written as the tuple of its coordinates, so `(1,0)` is the first versor of `Z2xZ4`,
+and the only element of the trivial module is `0`
+*/
+#[allow(
+    clippy::expect_used,
+    reason = "every element has an index below the cardinality"
+)]
+fn element_labels<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord>(
+    module: &CanonModule<R, I>,
+) -> Vec<String> {
+    let torsion_coeffs: Vec<u16> = module.torsion_coeffs_as_u16().collect();
+    let mut labels = vec![String::new(); module.cardinality()];
+    for element in module.elements() {
+        let label = match module.dimension() {
+            0 => "0".to_owned(),
+            _ => format!(
+                "({})",
+                element
+                    .clone()
+                    .into_values()
+                    .zip(&torsion_coeffs)
+                    .map(|(value, &coeff)| value
+                        .into()
+                        .checked_rem(coeff)
+                        .unwrap_or_default()
+                        .to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        };
+        *labels
+            .get_mut(element_index(&torsion_coeffs, element))
+            .expect("index calculated to be within range") = label;
+    }
+    labels
+}
+
+pub struct Pairs<'relation, R: Ring, I: Ideal<Parent = R> + Ord>(&'relation Relation<R, I>);
+
+pub struct Grid<'relation, R: Ring, I: Ideal<Parent = R> + Ord>(&'relation Relation<R, I>);
+
+impl<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord> fmt::Display
+    for Pairs<'_, R, I>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source_labels = element_labels(&self.0.source);
+        let target_labels = element_labels(&self.0.target);
+        write!(
+            f,
+            "{{{}}}",
+            source_labels
+                .iter()
+                .enumerate()
+                .flat_map(|(col, source_label)| {
+                    target_labels
+                        .iter()
+                        .enumerate()
+                        .filter(move |&(row, _)| self.0.matrix.get(col, row) == Some(true))
+                        .map(move |(_, target_label)| format!("{source_label} ~ {target_label}"))
+                })
+                .join(", ")
+        )
+    }
+}
+
+/**
+the columns are as wide as their labels, and the entries are aligned to the right
+*/
+impl<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord> fmt::Display
+    for Grid<'_, R, I>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source_labels = element_labels(&self.0.source);
+        let target_labels = element_labels(&self.0.target);
+        let width = source_labels.iter().map(String::len).max().unwrap_or(0);
+
+        writeln!(f, "{:width$} {}", "", target_labels.iter().join(" "))?;
+        for (col, source_label) in source_labels.iter().enumerate() {
+            writeln!(
+                f,
+                "{source_label:>width$} {}",
+                target_labels
+                    .iter()
+                    .enumerate()
+                    .map(|(row, target_label)| {
+                        let entry = match self.0.matrix.get(col, row) == Some(true) {
+                            true => '1',
+                            false => '.',
+                        };
+                        format!("{entry:>0$}", target_label.len())
+                    })
+                    .join(" ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
 impl<R: Ring, I: Ideal<Parent = R> + Ord> IsMap<CanonModule<R, I>> for Relation<R, I> {
     fn is_a_map(&self) -> bool {
-        self.matrix
-            .cols()
-            .all(|collumn| collumn.filter(|entry| **entry).count() == 1)
+        self.matrix.every_col_is_hit() && self.matrix.no_col_is_hit_twice()
     }
 }
 
 impl<R: Ring, I: Ideal<Parent = R> + Ord> IsMatching<CanonModule<R, I>> for Relation<R, I> {
     fn is_a_matching(&self) -> bool {
-        self.matrix
-            .cols()
-            .all(|col| col.filter(|entry| **entry).count() <= 1)
-            && self
-                .matrix
-                .rows()
-                .all(|row| row.filter(|entry| **entry).count() <= 1)
+        self.matrix.no_col_is_hit_twice() && self.matrix.row_counts().all(|count| count <= 1)
     }
 }
 
 impl<R: Ring, I: Ideal<Parent = R> + Ord> IsWide<CanonModule<R, I>> for Relation<R, I> {
     fn is_wide(&self) -> bool {
-        self.matrix
-            .cols()
-            .all(|col| col.filter(|entry| **entry).count() > 0)
-            && self
-                .matrix
-                .rows()
-                .all(|row| row.filter(|entry| **entry).count() > 0)
+        self.matrix.every_col_is_hit() && self.matrix.row_counts().all(|count| count > 0)
+    }
+}
+
+// the converse relation, the transposed matrix has the source cardinality as its number of rows
+impl<R: Ring, I: Ideal<Parent = R> + Ord> Dagger<CanonModule<R, I>> for Relation<R, I> {
+    fn dagger(&self) -> Self {
+        Self {
+            source: Arc::clone(&self.target),
+            target: Arc::clone(&self.source),
+            matrix: self.matrix.transpose(),
+        }
+    }
+}
+
+/**
+elements are indexed by their coordinates in mixed radix,
+the first torsion coefficient being the least significant one, as in the conversion from submodules
+*/
+#[allow(
+    clippy::arithmetic_side_effects,
+    reason = "the indices are bounded by the cardinality of the module"
+)]
+fn add_indices(torsion_coeffs: &[u16], left: usize, right: usize) -> usize {
+    torsion_coeffs
+        .iter()
+        .fold(
+            (left, right, 0, 1),
+            |(left_rest, right_rest, sum, shift), &coeff| {
+                let radix = usize::from(coeff);
+                (
+                    left_rest / radix,
+                    right_rest / radix,
+                    sum + (left_rest % radix + right_rest % radix) % radix * shift,
+                    shift * radix,
+                )
+            },
+        )
+        .2
+}
+
+/**
+additive relations from `a` to `b` are the submodules of `a + b`,
+so the meet is the intersection and the join is the sum of submodules,
+which in general is bigger than the union
+*/
+impl<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord> Allegory<CanonModule<R, I>>
+    for Relation<R, I>
+{
+    fn try_meet(&self, other: &Self) -> Option<Self> {
+        self.is_parallel_to(other).then(|| Self {
+            source: Arc::clone(&self.source),
+            target: Arc::clone(&self.target),
+            matrix: self.matrix.and(&other.matrix),
+        })
+    }
+
+    fn try_join(&self, other: &Self) -> Option<Self> {
+        self.is_parallel_to(other).then(|| {
+            let source_tc: Vec<u16> = self.source.torsion_coeffs_as_u16().collect();
+            let target_tc: Vec<u16> = self.target.torsion_coeffs_as_u16().collect();
+            let cols = self.matrix.nof_cols;
+            let pairs = |relation: &Self| -> Vec<(usize, usize)> {
+                relation
+                    .matrix
+                    .iter()
+                    .zip((0..relation.matrix.nof_rows).cartesian_product(0..cols))
+                    .filter(|&(entry, _crd)| entry)
+                    .map(|(_entry, (row, col))| (col, row))
+                    .collect()
+            };
+            let other_pairs = pairs(other);
+
+            let mut buffer = vec![false; cols.saturating_mul(self.matrix.nof_rows)];
+            for (self_source, self_target) in pairs(self) {
+                for &(other_source, other_target) in &other_pairs {
+                    let source_index = add_indices(&source_tc, self_source, other_source);
+                    let target_index = add_indices(&target_tc, self_target, other_target);
+                    *buffer
+                        .get_mut(source_index.saturating_add(cols.saturating_mul(target_index)))
+                        .expect("index calculated to be within range") = true;
+                }
+            }
+
+            Self {
+                source: Arc::clone(&self.source),
+                target: Arc::clone(&self.target),
+                matrix: BitMatrix::from_buffer(buffer, cols, self.matrix.nof_rows),
+            }
+        })
     }
+
+    fn is_contained_in(&self, other: &Self) -> bool {
+        self.is_parallel_to(other) && self.matrix.is_contained_in(&other.matrix)
+    }
+
+    fn top(source: Self::B, target: Self::B) -> Self {
+        let (cols, rows) = (source.cardinality(), target.cardinality());
+        Self {
+            source,
+            target,
+            matrix: BitMatrix::from_buffer(vec![true; cols.saturating_mul(rows)], cols, rows),
+        }
+    }
+
+    // the zero of the source is related only to the zero of the target, both have index 0
+    fn bottom(source: Self::B, target: Self::B) -> Self {
+        let (cols, rows) = (source.cardinality(), target.cardinality());
+        Self {
+            source,
+            target,
+            matrix: BitMatrix::from_buffer(
+                (0..cols.saturating_mul(rows)).map(|index| index == 0),
+                cols,
+                rows,
+            ),
+        }
+    }
+}
+
+// additive relations are difunctional, so their difunctional closures are trivial
+impl<R: Ring + Copy + Into<u16> + hash::Hash, I: PrincipalIdeal<Parent = R> + Ord + hash::Hash>
+    Endorelation<CanonModule<R, I>> for Relation<R, I>
+{
 }
 
 impl<R: Ring, I: Ideal<Parent = R> + Ord> IsBij<CanonModule<R, I>> for Relation<R, I> {
     fn is_a_bijection(&self) -> bool {
-        self.matrix
-            .cols()
-            .all(|col| col.filter(|entry| **entry).count() == 1)
-            && self
-                .matrix
-                .rows()
-                .all(|row| row.filter(|entry| **entry).count() == 1)
+        self.matrix.every_col_is_hit()
+            && self.matrix.no_col_is_hit_twice()
+            && self.matrix.row_counts().all(|count| count == 1)
+    }
+}
+
+/**
+the bits of the matrix in the order of its buffer,
+the dimensions are given by the source and the target
+*/
+impl<R: Ring + Copy, I: Ideal<Parent = R> + Ord> PersistentMorphism<CanonModule<R, I>>
+    for Relation<R, I>
+{
+    fn persist(&self, bytes: &mut Vec<u8>) {
+        self.matrix.iter().collect::<BitVec<u8>>().persist(bytes);
+    }
+
+    fn restore(source: Self::B, target: Self::B, bytes: &mut &[u8]) -> Option<Self> {
+        let bits = BitVec::<u8>::restore(bytes)?;
+        let nof_cols = source.cardinality();
+        let nof_rows = target.cardinality();
+        (Some(bits.len()) == nof_cols.checked_mul(nof_rows)).then(|| Self {
+            source,
+            target,
+            matrix: BitMatrix::from_buffer(bits.iter().by_vals(), nof_cols, nof_rows),
+        })
     }
 }
 
@@ -498,7 +1207,7 @@ mod test {
 
         assert_eq!(relations_zn_out.len(), 6);
 
-        let matrices_zn_out: Vec<Matrix<bool>> = relations_zn_out
+        let matrices_zn_out: Vec<BitMatrix> = relations_zn_out
             .into_iter()
             .map(|relation| relation.matrix)
             .collect();
@@ -520,8 +1229,8 @@ mod test {
                     .map(|bool| bool == 1)
                     .collect::<Vec<bool>>()
             })
-            .map(|buffer| Matrix::from_buffer(buffer, 3, 3))
-            .collect::<Vec<Matrix<bool>>>();
+            .map(|buffer| BitMatrix::from_buffer(buffer, 3, 3))
+            .collect::<Vec<BitMatrix>>();
 
         for matrix_ok in matrices_zn_ok.iter() {
             assert!(matrices_zn_out
@@ -605,7 +1314,7 @@ mod test {
 
         assert_eq!(relations_zn_out.len(), 6);
 
-        let matrices_zn_out: Vec<Matrix<bool>> = relations_zn_out
+        let matrices_zn_out: Vec<BitMatrix> = relations_zn_out
             .into_iter()
             .map(|relation| relation.matrix)
             .collect();
@@ -627,8 +1336,8 @@ mod test {
                     .map(|bool| bool == 1)
                     .collect::<Vec<bool>>()
             })
-            .map(|buffer| Matrix::from_buffer(buffer, 3, 3))
-            .collect::<Vec<Matrix<bool>>>();
+            .map(|buffer| BitMatrix::from_buffer(buffer, 3, 3))
+            .collect::<Vec<BitMatrix>>();
 
         for matrix_ok in matrices_zn_ok.iter() {
             assert!(matrices_zn_out
@@ -708,6 +1417,228 @@ mod test {
         );
     }
 
+    #[test]
+    fn symmetric_endorelations() {
+        use typenum::U3 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+
+        let category = Category::<CanonModule<R, I>, Relation<R, I>>::new(1);
+        let z3 = CanonModule::<R, I>::from_iter([3]);
+        let endos = category.hom_set(&z3, &z3);
+
+        // zero, the diagonal, the antidiagonal and everything
+        assert_eq!(endos.iter().filter(|endo| endo.is_self_dagger()).count(), 4);
+        for map in endos.iter().filter(|endo| endo.is_a_map()) {
+            assert_eq!(map.dagger().is_a_map(), map.is_a_bijection());
+        }
+    }
+
+    #[test]
+    fn join_is_the_sum_of_submodules() {
+        use typenum::U2 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+
+        let z2 = Arc::new(CanonModule::<R, I>::from_iter([2]));
+        let bottom = Relation::<R, I>::bottom(Arc::clone(&z2), Arc::clone(&z2));
+        let top = Relation::<R, I>::top(Arc::clone(&z2), Arc::clone(&z2));
+        let identity = Relation::<R, I>::identity(Arc::clone(&z2));
+        let twist = Relation {
+            source: Arc::clone(&z2),
+            target: Arc::clone(&z2),
+            matrix: BitMatrix::from_buffer([true, false, true, false], 2, 2),
+        };
+
+        assert!(bottom.is_contained_in(&identity) && identity.is_contained_in(&top));
+        assert_eq!(identity.try_meet(&twist), Some(bottom));
+        // the union of the two has three elements, their sum has all four
+        assert_eq!(identity.try_join(&twist), Some(top));
+    }
+
+    #[test]
+    fn closures_of_endorelations() {
+        use typenum::U4 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+        type Closure = fn(&Relation<R, I>) -> Option<Relation<R, I>>;
+        type Property = fn(&Relation<R, I>) -> bool;
+
+        let category = Category::<CanonModule<R, I>, Relation<R, I>>::new(1);
+        let endos: Vec<Relation<R, I>> = category
+            .into_morphisms()
+            .into_iter()
+            .filter(Endorelation::is_an_endo)
+            .collect();
+
+        let z2 = Arc::new(CanonModule::<R, I>::from_iter([2]));
+        let count = |property: Property| {
+            endos
+                .iter()
+                .filter(|endo| *endo.source() == *z2 && property(endo))
+                .count()
+        };
+        // the five subgroups of z2 + z2
+        assert_eq!(count(|_| true), 5);
+        assert_eq!(count(Endorelation::is_reflexive), 2);
+        assert_eq!(count(Endorelation::is_symmetric), 3);
+        assert_eq!(count(Endorelation::is_transitive), 5);
+        assert_eq!(count(Endorelation::is_an_equivalence), 2);
+        assert_eq!(count(Endorelation::is_a_partial_equivalence), 3);
+        assert_eq!(count(Endorelation::is_idempotent), 5);
+
+        let closures: [(Closure, Property); 3] = [
+            (
+                Endorelation::try_reflexive_closure,
+                Endorelation::is_reflexive,
+            ),
+            (
+                Endorelation::try_transitive_closure,
+                Endorelation::is_transitive,
+            ),
+            (
+                Endorelation::try_difunctional_closure,
+                Endorelation::is_difunctional,
+            ),
+        ];
+        for endo in &endos {
+            assert!(endo.is_difunctional(), "{}", endo.pairs());
+            assert_eq!(
+                endo.is_an_equivalence(),
+                endo.is_reflexive() && endo.is_a_partial_equivalence()
+            );
+            for &(try_closure, property) in &closures {
+                let closure = try_closure(endo).expect("the relation is an endorelation");
+                assert!(endo.is_contained_in(&closure) && property(&closure));
+                // the least one with the property
+                for other in &endos {
+                    if endo.is_contained_in(other) && property(other) {
+                        assert!(closure.is_contained_in(other), "{}", endo.pairs());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lambek_invariants() {
+        use crate::{category::morphism::PreAbelian, ralg::ring::AdditivePartialMonoid};
+        use typenum::U4 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+
+        for relation in Category::<CanonModule<R, I>, Relation<R, I>>::new(1).into_morphisms() {
+            let invariants = relation.lambek_invariants();
+            let related = |col: usize, row: usize| {
+                relation
+                    .matrix
+                    .get(col, row)
+                    .expect("the indices are in proper bounds")
+            };
+            let cols = 0..relation.matrix.nof_cols;
+            let rows = 0..relation.matrix.nof_rows;
+
+            let card = |map: &CanonToCanon<R, I>| map.source().cardinality();
+            assert_eq!(
+                card(&invariants.domain),
+                cols.clone()
+                    .filter(|&col| rows.clone().any(|row| related(col, row)))
+                    .count()
+            );
+            assert_eq!(
+                card(&invariants.kernel),
+                cols.clone().filter(|&col| related(col, 0)).count()
+            );
+            assert_eq!(
+                card(&invariants.image),
+                rows.clone()
+                    .filter(|&row| cols.clone().any(|col| related(col, row)))
+                    .count()
+            );
+            assert_eq!(
+                card(&invariants.indeterminacy),
+                rows.clone().filter(|&row| related(0, row)).count()
+            );
+
+            for inclusion in [
+                &invariants.domain,
+                &invariants.kernel,
+                &invariants.image,
+                &invariants.indeterminacy,
+            ] {
+                assert!(inclusion.kernel().is_zero());
+            }
+            assert!(invariants.isomorphism.kernel().is_zero());
+            assert!(invariants.isomorphism.cokernel().is_zero());
+        }
+    }
+
+    #[test]
+    fn tabulations_are_spans_of_relations() {
+        use typenum::{U4 as N, U6};
+        type R = C<N>;
+        type I = CIdeal<N>;
+        type S = C<U6>;
+        type J = CIdeal<U6>;
+
+        for relation in Category::<CanonModule<R, I>, Relation<R, I>>::new(1).into_morphisms() {
+            let (left, right) = relation.tabulate();
+            assert_eq!(left.target(), relation.source());
+            assert_eq!(right.target(), relation.target());
+            assert_eq!(Relation::try_from_span(&left, &right), Some(relation));
+        }
+
+        // the copies of Z6 spanned by the pairs are split into Z2 and Z3
+        for relation in Category::<CanonModule<S, J>, Relation<S, J>>::new(1).into_morphisms() {
+            let (left, right) = relation.tabulate();
+            assert_eq!(Relation::try_from_span(&left, &right), Some(relation));
+        }
+    }
+
+    #[test]
+    fn cospans_are_composites_of_graphs() {
+        use typenum::U4 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+
+        let z2 = Arc::new(CanonModule::<R, I>::from_iter([2]));
+        let z4 = Arc::new(CanonModule::<R, I>::from_iter([4]));
+        let graph = |map: &CanonToCanon<R, I>| {
+            let identity = CanonToCanon::new(
+                &map.source(),
+                &map.source(),
+                Matrix::from_buffer([R::from(1)], 1, 1),
+            );
+            Relation::try_from_span(&identity, map).expect("the legs have the same source")
+        };
+
+        for left in CanonToCanon::hom(Arc::clone(&z4), Arc::clone(&z2)) {
+            for right in CanonToCanon::hom(Arc::clone(&z2), Arc::clone(&z2)) {
+                assert_eq!(
+                    Relation::try_from_cospan(&left, &right),
+                    Some(graph(&left).compose(&graph(&right).dagger()))
+                );
+            }
+            // the kernel pair of a map is an equivalence relation
+            let kernel_pair =
+                Relation::try_from_cospan(&left, &left).expect("the legs have the same target");
+            assert!(Relation::identity(Arc::clone(&z4)).is_contained_in(&kernel_pair));
+            assert!(kernel_pair.is_self_dagger());
+            assert!(kernel_pair
+                .compose(&kernel_pair)
+                .is_contained_in(&kernel_pair));
+        }
+        assert!(Relation::try_from_span(
+            &CanonToCanon::hom(Arc::clone(&z2), Arc::clone(&z4))
+                .next()
+                .unwrap(),
+            &CanonToCanon::hom(Arc::clone(&z4), Arc::clone(&z2))
+                .next()
+                .unwrap()
+        )
+        .is_none());
+    }
+
     #[test]
     fn identity_morphism() {
         use typenum::U2 as N;
@@ -724,4 +1655,50 @@ mod test {
             assert_eq!(morphism, morphism.compose(&id_target));
         }
     }
+
+    #[test]
+    fn json_of_identity() {
+        use typenum::U4 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+
+        let z4 = Arc::new(CanonModule::<R, I>::from_iter([4]));
+        let identity = Relation::<R, I>::identity(z4);
+
+        assert_eq!(
+            identity.to_json().to_string(),
+            r#"{"source":[4],"target":[4],"matrix":["1000","0100","0010","0001"]}"#
+        );
+    }
+
+    #[test]
+    fn element_level_display() {
+        use typenum::U4 as N;
+        type R = C<N>;
+        type I = CIdeal<N>;
+
+        let z2 = Arc::new(CanonModule::<R, I>::from_iter([2]));
+        let z4 = Arc::new(CanonModule::<R, I>::from_iter([4]));
+        let doubling = Relation::graph(&CanonToCanon::new(
+            &z2,
+            &z4,
+            Matrix::from_buffer([R::from(2)], 1, 1),
+        ));
+
+        assert_eq!(doubling.pairs().to_string(), "{(0) ~ (0), (1) ~ (2)}");
+        assert_eq!(
+            doubling.grid().to_string(),
+            "    (0) (1) (2) (3)\n(0)   1   .   .   .\n(1)   .   .   1   .\n"
+        );
+
+        let z2z2 = Arc::new(CanonModule::<R, I>::from_iter([2, 2]));
+        let zero = Arc::new(CanonModule::<R, I>::default());
+        let top = Relation::top(Arc::clone(&z2z2), zero);
+        assert_eq!(
+            top.pairs().to_string(),
+            "{(0,0) ~ 0, (1,0) ~ 0, (0,1) ~ 0, (1,1) ~ 0}",
+            "{}",
+            top.grid()
+        );
+    }
 }
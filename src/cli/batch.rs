@@ -0,0 +1,582 @@
+use crate::{
+    category::functors::EndoFilter,
+    cli::{execute, Backend, Command, Config, Error, Format, DEFAULT_CHECKPOINT_SECONDS},
+    Int,
+};
+use rayon::prelude::*;
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    io::{self, Write},
+    panic,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/* # job files */
+
+/**
+a job file describes a sweep over the moduli and the maximal dimensions:
+```text
+# every supported ring up to dimension 2
+stage = szymczak-full
+moduli = 2..=62
+max-dim = 2
+recursion = 8
+out = results/szymczak-wide-full/txt/dim{dim}/Z{modulus}-dim-{dim}
+summary = results/szymczak-wide-full/summary
+parallel = false
+checkpoint = results/szymczak-wide-full/checkpoints/dim{dim}/Z{modulus}
+checkpoint-every = 600
+lazy = false
+```
+every pair of a modulus and a dimension is a separate job,
+`{modulus}` and `{dim}` in the output and checkpoint paths are replaced by its parameters.
+lists of numbers are separated by commas and may contain ranges `a..=b`.
+running the file again resumes every unfinished job from its checkpoints.
+`endos = <filter>`, `cache-limit = <morphisms>`, `skeleton = true`,
+`composition-tables = true` and `index = true` work as the flags of the command line.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobFile {
+    pub stage: Command,
+    pub moduli: Vec<u16>,
+    pub max_dims: Vec<Int>,
+    pub recursion: usize,
+    pub endos: EndoFilter,
+    pub format: Format,
+    pub out: String,
+    pub summary: PathBuf,
+    pub parallel: bool,
+    pub checkpoint: Option<String>,
+    pub checkpoint_every: Duration,
+    pub backend: Backend,
+    pub skeleton: bool,
+    pub index: bool,
+}
+
+fn invalid<T: fmt::Display>(line: usize, reason: T) -> Error {
+    Error::InvalidJobFile {
+        line: Some(line),
+        reason: reason.to_string(),
+    }
+}
+
+fn parse_numbers(list: &str) -> Option<Vec<u16>> {
+    list.split(',')
+        .map(|item| match item.trim().split_once("..=") {
+            Some((start, end)) => Some(start.trim().parse().ok()?..=end.trim().parse().ok()?),
+            None => item.trim().parse().ok().map(|number| number..=number),
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|ranges| ranges.into_iter().flatten().collect())
+}
+
+/**
+the backend once one of the keys `lazy`, `cache-limit` or `composition-tables` is read
+*/
+fn with_backend(line: usize, backend: Backend, key: &str, value: &str) -> Result<Backend, Error> {
+    let requested = match key {
+        "cache-limit" => {
+            Backend::Lazy(Some(value.parse().map_err(|_err| {
+                invalid(line, "cache-limit should be a number of morphisms")
+            })?))
+        }
+        "lazy" if parse_boolean(line, key, value)? => Backend::Lazy(None),
+        "composition-tables" if parse_boolean(line, key, value)? => Backend::CompositionTables,
+        _ => Backend::Eager,
+    };
+    backend.and(requested).map_err(|_err| {
+        invalid(
+            line,
+            "the lazy category and the composition tables cannot be used together",
+        )
+    })
+}
+
+fn parse_boolean(line: usize, key: &str, value: &str) -> Result<bool, Error> {
+    value
+        .parse()
+        .map_err(|_err| invalid(line, format!("{key} should be `true` or `false`")))
+}
+
+impl JobFile {
+    /**
+    the summary goes next to the job file, unless the file says otherwise.
+    */
+    pub fn parse(contents: &str, default_summary: PathBuf) -> Result<Self, Error> {
+        let mut stage = Command::SzymczakFull;
+        let mut moduli = None;
+        let mut max_dims = vec![2];
+        let mut recursion = 8;
+        let mut endos = EndoFilter::default();
+        let mut format = Format::Txt;
+        let mut out = None;
+        let mut summary = default_summary;
+        let mut parallel = false;
+        let mut checkpoint = None;
+        let mut checkpoint_every = Duration::from_secs(DEFAULT_CHECKPOINT_SECONDS);
+        let mut backend = Backend::default();
+        let mut skeleton = false;
+        let mut index = false;
+
+        for (line, raw_line) in (1_usize..).zip(contents.lines()) {
+            let content = raw_line
+                .split_once('#')
+                .map_or(raw_line, |(content, _comment)| content)
+                .trim();
+            if content.is_empty() {
+                continue;
+            }
+            let (key, raw_value) = content
+                .split_once('=')
+                .ok_or_else(|| invalid(line, "expected `key = value`"))?;
+            let value = raw_value.trim();
+            match key.trim() {
+                "stage" => {
+                    stage = match value {
+                        "category" => Command::Category,
+                        "szymczak" => Command::Szymczak,
+                        "szymczak-full" => Command::SzymczakFull,
+                        _ => return Err(invalid(line, format!("unknown stage `{value}`"))),
+                    }
+                }
+                "moduli" => {
+                    moduli = Some(
+                        parse_numbers(value)
+                            .ok_or_else(|| invalid(line, "moduli should be a list of numbers"))?,
+                    );
+                }
+                "max-dim" => {
+                    max_dims = parse_numbers(value)
+                        .ok_or_else(|| invalid(line, "dimensions should be a list of numbers"))?;
+                }
+                "recursion" => {
+                    recursion = value
+                        .parse()
+                        .map_err(|_err| invalid(line, "recursion should be a number"))?;
+                }
+                "endos" => {
+                    endos = value.parse().map_err(|()| {
+                        invalid(line, format!("unknown endomorphism filter `{value}`"))
+                    })?;
+                }
+                "format" => {
+                    format = value
+                        .parse()
+                        .map_err(|()| invalid(line, format!("unknown format `{value}`")))?;
+                }
+                "out" => out = Some(value.to_owned()),
+                "summary" => summary = PathBuf::from(value),
+                "parallel" => parallel = parse_boolean(line, "parallel", value)?,
+                "checkpoint" => checkpoint = Some(value.to_owned()),
+                "checkpoint-every" => {
+                    checkpoint_every = Duration::from_secs(value.parse().map_err(|_err| {
+                        invalid(line, "checkpoint-every should be a number of seconds")
+                    })?);
+                }
+                "lazy" | "cache-limit" | "composition-tables" => {
+                    backend = with_backend(line, backend, key.trim(), value)?;
+                }
+                "skeleton" => skeleton = parse_boolean(line, "skeleton", value)?,
+                "index" => index = parse_boolean(line, "index", value)?,
+                unknown => return Err(invalid(line, format!("unknown key `{unknown}`"))),
+            }
+        }
+
+        Ok(Self {
+            stage,
+            moduli: moduli.ok_or_else(|| Error::InvalidJobFile {
+                line: None,
+                reason: "moduli are not specified".to_owned(),
+            })?,
+            max_dims,
+            recursion,
+            endos,
+            format,
+            out: out.ok_or_else(|| Error::InvalidJobFile {
+                line: None,
+                reason: "output path is not specified".to_owned(),
+            })?,
+            summary,
+            parallel,
+            checkpoint,
+            checkpoint_every,
+            backend,
+            skeleton,
+            index,
+        })
+    }
+
+    /**
+    all the jobs of the sweep, each of them validated like a single run.
+    two jobs writing to the same file are rejected,
+    since one of them would be skipped when resuming.
+    */
+    #[allow(
+        clippy::literal_string_with_formatting_args,
+        reason = "these are the placeholders of the job file"
+    )]
+    pub fn jobs(&self) -> Result<Vec<Config>, Error> {
+        let jobs: Vec<Config> = self
+            .moduli
+            .iter()
+            .flat_map(|&modulus| {
+                self.max_dims.iter().map(move |&max_dim| {
+                    let substitute = |template: &str| {
+                        PathBuf::from(
+                            template
+                                .replace("{modulus}", &modulus.to_string())
+                                .replace("{dim}", &max_dim.to_string()),
+                        )
+                    };
+                    Config {
+                        command: self.stage.clone(),
+                        modulus,
+                        max_dim,
+                        recursion: self.recursion,
+                        endos: self.endos,
+                        out: Some(substitute(&self.out)),
+                        format: self.format,
+                        checkpoint: self.checkpoint.as_deref().map(substitute),
+                        checkpoint_every: self.checkpoint_every,
+                        resume: true,
+                        backend: self.backend,
+                        skeleton: self.skeleton,
+                        index: self.index,
+                    }
+                })
+            })
+            .collect();
+
+        let mut outs = HashSet::new();
+        for job in &jobs {
+            job.validate()?;
+            if !outs.insert(&job.out) {
+                return Err(Error::InvalidJobFile {
+                    line: None,
+                    reason: format!(
+                        "several jobs write to the same file, Z{} dim {} among them",
+                        job.modulus, job.max_dim
+                    ),
+                });
+            }
+        }
+        Ok(jobs)
+    }
+}
+
+/* # running */
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Done(Duration),
+    Skipped,
+    Failed(String),
+}
+
+pub struct Report {
+    pub job: Config,
+    pub outcome: Outcome,
+}
+
+fn job_name(job: &Config) -> String {
+    format!("Z{} dim {}", job.modulus, job.max_dim)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", job_name(&self.job))?;
+        match self.outcome {
+            Outcome::Done(elapsed) => write!(f, "done after {}", elapsed.as_secs_f64()),
+            Outcome::Skipped => write!(f, "skipped, the output already exists"),
+            Outcome::Failed(ref reason) => write!(f, "failed: {}", reason.replace('\n', " ")),
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panicked".to_owned())
+}
+
+/**
+a job is finished once its output exists, see `pipeline::emit`.
+a panic inside a job is recorded as its failure and the others carry on.
+*/
+fn run_job(job: Config) -> Report {
+    if job.out.as_ref().is_some_and(|out| out.exists()) {
+        return Report {
+            job,
+            outcome: Outcome::Skipped,
+        };
+    }
+    let job_time = Instant::now();
+    let outcome = match panic::catch_unwind(|| execute(&job)) {
+        Ok(Ok(())) => Outcome::Done(job_time.elapsed()),
+        Ok(Err(error)) => Outcome::Failed(error.to_string()),
+        Err(payload) => Outcome::Failed(panic_message(payload.as_ref())),
+    };
+    let report = Report { job, outcome };
+    println!("{report}");
+    report
+}
+
+/**
+the reports of the jobs done in an earlier run of the job file, by the names of the jobs.
+they are kept in the summary when the jobs are skipped, so their timings are not lost
+*/
+fn earlier_reports(summary: &Path) -> HashMap<String, String> {
+    fs::read_to_string(summary)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            line.split_once(": ")
+                .filter(|&(_name, outcome)| outcome.starts_with("done after "))
+                .map(|(name, _outcome)| (name.to_owned(), line.to_owned()))
+        })
+        .collect()
+}
+
+/**
+the report of every job is appended to the summary as soon as the job finishes,
+so a sweep killed partway through leaves the record of the finished jobs.
+the totals are written once all of them are finished
+*/
+struct Summary {
+    file: Mutex<fs::File>,
+    earlier: HashMap<String, String>,
+}
+
+impl Summary {
+    fn create(path: &Path, job_file: &Path) -> io::Result<Self> {
+        let earlier = earlier_reports(path);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut file = fs::File::create(path)?;
+        write!(file, "Job file: {}\n===\n", job_file.display())?;
+        Ok(Self {
+            file: Mutex::new(file),
+            earlier,
+        })
+    }
+
+    fn record(&self, report: Report) -> io::Result<Report> {
+        let line = match report.outcome {
+            Outcome::Skipped => self.earlier.get(&job_name(&report.job)).cloned(),
+            Outcome::Done(_) | Outcome::Failed(_) => None,
+        }
+        .unwrap_or_else(|| report.to_string());
+        writeln!(
+            self.file.lock().unwrap_or_else(PoisonError::into_inner),
+            "{line}"
+        )?;
+        Ok(report)
+    }
+
+    fn finish(self, reports: &[Report], elapsed: Duration) -> io::Result<()> {
+        let count = |predicate: fn(&Outcome) -> bool| {
+            reports
+                .iter()
+                .filter(|report| predicate(&report.outcome))
+                .count()
+        };
+        write!(
+            self.file
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner),
+            "===\nJobs: {}\nDone: {}\nSkipped: {}\nFailed: {}\nBatch finished after: {}\n",
+            reports.len(),
+            count(|outcome| matches!(*outcome, Outcome::Done(_))),
+            count(|outcome| matches!(*outcome, Outcome::Skipped)),
+            count(|outcome| matches!(*outcome, Outcome::Failed(_))),
+            elapsed.as_secs_f64()
+        )
+    }
+}
+
+pub fn run(job_file: &Path) -> Result<(), Error> {
+    let description = JobFile::parse(
+        &fs::read_to_string(job_file)?,
+        job_file.with_extension("summary"),
+    )?;
+    let jobs = description.jobs()?;
+
+    let summary = Summary::create(&description.summary, job_file)?;
+    let batch_time = Instant::now();
+    let reports: Vec<Report> = match description.parallel {
+        true => jobs
+            .into_par_iter()
+            .map(|job| summary.record(run_job(job)))
+            .collect::<io::Result<_>>()?,
+        false => jobs
+            .into_iter()
+            .map(|job| summary.record(run_job(job)))
+            .collect::<io::Result<_>>()?,
+    };
+    summary.finish(&reports, batch_time.elapsed())?;
+
+    match reports
+        .iter()
+        .filter(|report| matches!(report.outcome, Outcome::Failed(_)))
+        .count()
+    {
+        0 => Ok(()),
+        nof_failed => Err(Error::FailedJobs(nof_failed)),
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parsing_job_files() {
+        let description = JobFile::parse(
+            "# a comment\nstage = szymczak\nmoduli = 2..=4, 6\nmax-dim = 1, 2\n\nout = res/Z{modulus}-dim-{dim} # trailing comment\nparallel = true\n",
+            PathBuf::from("sweep.summary"),
+        )
+        .expect("job file is valid");
+        assert_eq!(description.stage, Command::Szymczak);
+        assert_eq!(description.moduli, vec![2, 3, 4, 6]);
+        assert_eq!(description.max_dims, vec![1, 2]);
+        assert_eq!(description.recursion, 8, "default recursion parameter");
+        assert_eq!(description.summary, PathBuf::from("sweep.summary"));
+        assert!(description.parallel);
+
+        let jobs = description.jobs().expect("jobs are valid");
+        assert_eq!(jobs.len(), 8);
+        assert_eq!(
+            jobs.last().map(|job| job.out.clone()),
+            Some(Some(PathBuf::from("res/Z6-dim-2")))
+        );
+        assert!(
+            jobs.iter().all(|job| job.checkpoint.is_none()),
+            "no checkpoints unless asked for"
+        );
+
+        let checkpointed = JobFile::parse(
+            "moduli = 4
+out = res/Z{modulus}
+checkpoint = ckpt/Z{modulus}-dim-{dim}
+checkpoint-every = 90
+cache-limit = 5000
+skeleton = true
+endos = bijections
+index = true
+",
+            PathBuf::from("sweep.summary"),
+        )
+        .expect("job file is valid")
+        .jobs()
+        .expect("jobs are valid");
+        assert_eq!(
+            checkpointed.first().map(|job| job.checkpoint.clone()),
+            Some(Some(PathBuf::from("ckpt/Z4-dim-2")))
+        );
+        assert!(
+            checkpointed
+                .iter()
+                .all(|job| job.resume && job.checkpoint_every == Duration::from_secs(90)),
+            "jobs of a batch always resume"
+        );
+        assert!(
+            checkpointed
+                .iter()
+                .all(|job| job.endos == EndoFilter::Bijections),
+            "endomorphism filter"
+        );
+        assert!(
+            checkpointed
+                .iter()
+                .all(|job| job.backend == Backend::Lazy(Some(5000))),
+            "a cache limit implies the lazy category"
+        );
+        assert!(checkpointed.iter().all(|job| job.skeleton && job.index));
+    }
+
+    #[test]
+    fn rejecting_job_files() {
+        let parse = |contents: &str| JobFile::parse(contents, PathBuf::from("summary"));
+
+        assert!(matches!(
+            parse("moduli = 2\nout = res\nstage = leray"),
+            Err(Error::InvalidJobFile { line: Some(3), .. })
+        ));
+        assert!(matches!(
+            parse("moduli = 2..=x\nout = res"),
+            Err(Error::InvalidJobFile { line: Some(1), .. })
+        ));
+        assert!(matches!(
+            parse("out = res"),
+            Err(Error::InvalidJobFile { line: None, .. })
+        ));
+        assert!(matches!(
+            parse("moduli = 2, 3\nout = res/dim{dim}")
+                .expect("job file is valid")
+                .jobs(),
+            Err(Error::InvalidJobFile { line: None, .. })
+        ));
+        assert!(matches!(
+            parse("moduli = 1..=3\nout = res/Z{modulus}-dim-{dim}")
+                .expect("job file is valid")
+                .jobs(),
+            Err(Error::UnsupportedModulus(1))
+        ));
+    }
+
+    #[test]
+    fn running_and_resuming() {
+        let directory = std::env::temp_dir().join(format!("szymczak-batch-{}", std::process::id()));
+        let job_file = directory.join("sweep.jobs");
+        fs::create_dir_all(&directory).expect("temporary directory is writable");
+        fs::write(
+            &job_file,
+            format!(
+                "stage = szymczak\nmoduli = 2, 3\nmax-dim = 1\nout = {}/Z{{modulus}}-dim-{{dim}}\nindex = true\n",
+                directory.display()
+            ),
+        )
+        .expect("temporary directory is writable");
+
+        run(&job_file).expect("jobs should succeed");
+        let first =
+            fs::read_to_string(directory.join("sweep.summary")).expect("summary is written");
+        assert!(first.contains("Done: 2\nSkipped: 0\nFailed: 0"), "{first}");
+        assert!(directory.join("Z3-dim-1").exists());
+        assert!(
+            directory.join("Z3-dim-1.index").exists(),
+            "the index is written next to the classes"
+        );
+
+        fs::remove_file(directory.join("Z3-dim-1")).expect("output exists");
+        run(&job_file).expect("jobs should succeed");
+        let second =
+            fs::read_to_string(directory.join("sweep.summary")).expect("summary is written");
+        assert!(
+            second.contains("Done: 1\nSkipped: 1\nFailed: 0"),
+            "{second}"
+        );
+        let earlier_line = |summary: &str| {
+            summary
+                .lines()
+                .find(|line| line.starts_with("Z2 dim 1: "))
+                .map(str::to_owned)
+        };
+        assert_eq!(
+            earlier_line(&second),
+            earlier_line(&first),
+            "the timing of a skipped job is kept"
+        );
+
+        fs::remove_dir_all(directory).expect("temporary directory is removable");
+    }
+}
@@ -0,0 +1,676 @@
+use crate::{
+    category::functors::EndoFilter,
+    ralg::{cgroup::Radix, module::canon::object::ParseError as ModuleParseError},
+    results::text::ParseError as ResultParseError,
+    Int,
+};
+use const_format::formatcp;
+use seq_macro::seq;
+use std::{fmt, io, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+use typenum::{IsGreater, U1};
+
+mod batch;
+mod pipeline;
+
+/* # parameters of a run */
+
+pub const MAX_MODULUS: u16 = 62;
+
+pub const USAGE: &str = formatcp!(
+    "usage: szymczak_leray <command> --modulus <n> [options]
+       szymczak_leray batch <job file>
+       szymczak_leray convert <result> [--out <path>] [--format <f>]
+commands:
+  category          modules up to the maximal dimension and the relations between them
+  hom <A> <B>       relations between the modules A and B, written as `0`, `Z4` or `Z2xZ4`
+  submodules <A>    submodules of the module A
+  szymczak          szymczak classes of the wide endorelations
+  szymczak-full     szymczak classes together with all isomorphisms between their members
+  batch <file>      runs every job described in the file, skipping the finished ones
+  convert <file>    reads a result in the text format and writes it in another format
+                    (json by default)
+options:
+  --modulus <n>    order of the cyclic ring Z/n, supported values are 2..={MAX_MODULUS}
+  --max-dim <d>    maximal dimension of the modules in the category (default 2)
+  --recursion <k>  size below which the functor stops splitting the endomorphisms (default 8)
+  --endos <e>      endomorphisms to be classified, one of: all, wide, maps, matchings,
+                   bijections (default wide)
+  --out <path>     file to write the result to, missing directories are created
+                   (default: standard output)
+  --format <f>     format of the result, one of: txt, debug, json (default txt),
+                   the relations of hom can also be written as pairs or as a grid
+  --checkpoint <dir>
+                   directory to save the state of the category and of the classes to
+  --checkpoint-every <s>
+                   seconds between the checkpoints (default {DEFAULT_CHECKPOINT_SECONDS})
+  --resume         continues from the checkpoints instead of starting over
+  --lazy           generates the hom-sets when the functor needs them instead of up front
+  --cache-limit <m>
+                   number of morphisms the lazy category keeps, implies --lazy
+                   (default: unlimited)
+  --skeleton       keeps one module of every isomorphism type and reports the duplicates
+  --composition-tables
+                   numbers the morphisms and looks their composites up in tables,
+                   cannot be combined with --lazy, --checkpoint or --index
+  --index          saves one endomorphism of every szymczak class next to the result,
+                   to the path given by --out with `.index` appended"
+);
+
+pub const DEFAULT_CHECKPOINT_SECONDS: u64 = 600;
+
+/**
+the stage of the computation to be run.
+modules are kept as written, since they can only be parsed
+once the ring is known.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Command {
+    Category,
+    Hom(String, String),
+    Submodules(String),
+    #[default]
+    Szymczak,
+    SzymczakFull,
+    Batch(PathBuf),
+    Convert(PathBuf),
+}
+
+/**
+the format of the result. the relations of a hom-set can also be written
+element by element, as their related pairs or as grids.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Txt,
+    Debug,
+    Json,
+    Pairs,
+    Grid,
+}
+
+impl Format {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Txt => "txt",
+            Self::Debug => "debug",
+            Self::Json => "json",
+            Self::Pairs => "pairs",
+            Self::Grid => "grid",
+        }
+    }
+}
+
+/**
+how the hom-sets are held while the functors run:
+all of them generated up front, generated once needed with at most the given number
+of morphisms cached, or numbered in an arena composing through tables.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    #[default]
+    Eager,
+    Lazy(Option<usize>),
+    CompositionTables,
+}
+
+impl Backend {
+    const fn flag(self) -> &'static str {
+        match self {
+            Self::Eager => "",
+            Self::Lazy(None) => "--lazy",
+            Self::Lazy(Some(_)) => "--cache-limit",
+            Self::CompositionTables => "--composition-tables",
+        }
+    }
+
+    /**
+    the backend once another one is asked for, a cache limit being kept for the lazy one
+    */
+    const fn and(self, requested: Self) -> Result<Self, Error> {
+        match (self, requested) {
+            (Self::Eager, _)
+            | (Self::Lazy(_), Self::Lazy(Some(_)))
+            | (Self::CompositionTables, Self::CompositionTables) => Ok(requested),
+            (_, Self::Eager) | (Self::Lazy(_), Self::Lazy(None)) => Ok(self),
+            (Self::Lazy(_), Self::CompositionTables) | (Self::CompositionTables, Self::Lazy(_)) => {
+                Err(Error::ConflictingFlags(self.flag(), requested.flag()))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub command: Command,
+    pub modulus: u16,
+    pub max_dim: Int,
+    pub recursion: usize,
+    pub endos: EndoFilter,
+    pub out: Option<PathBuf>,
+    pub format: Format,
+    pub checkpoint: Option<PathBuf>,
+    pub checkpoint_every: Duration,
+    pub resume: bool,
+    pub backend: Backend,
+    pub skeleton: bool,
+    pub index: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            command: Command::default(),
+            modulus: 0,
+            max_dim: 2,
+            recursion: 8,
+            endos: EndoFilter::default(),
+            out: None,
+            format: Format::default(),
+            checkpoint: None,
+            checkpoint_every: Duration::from_secs(DEFAULT_CHECKPOINT_SECONDS),
+            resume: false,
+            backend: Backend::default(),
+            skeleton: false,
+            index: false,
+        }
+    }
+}
+
+/* ## errors */
+
+pub enum Error {
+    Help,
+    MissingCommand,
+    UnknownCommand(String),
+    WrongArguments(String),
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidValue {
+        flag: String,
+        value: String,
+    },
+    InvalidModule {
+        module: String,
+        error: ModuleParseError,
+    },
+    MissingModulus,
+    UnsupportedModulus(u16),
+    RecursionTooSmall(usize),
+    ConflictingFlags(&'static str, &'static str),
+    RequiredFlag(&'static str, &'static str),
+    InvalidJobFile {
+        line: Option<usize>,
+        reason: String,
+    },
+    FailedJobs(usize),
+    InvalidResult {
+        file: PathBuf,
+        error: ResultParseError,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Help => write!(f, "{USAGE}"),
+            Self::MissingCommand => write!(f, "the command has to be specified\n{USAGE}"),
+            Self::UnknownCommand(ref command) => {
+                write!(f, "unknown command `{command}`\n{USAGE}")
+            }
+            Self::WrongArguments(ref command) => {
+                write!(f, "wrong arguments for command `{command}`\n{USAGE}")
+            }
+            Self::UnknownFlag(ref flag) => write!(f, "unknown flag `{flag}`\n{USAGE}"),
+            Self::MissingValue(ref flag) => write!(f, "flag `{flag}` requires a value\n{USAGE}"),
+            Self::InvalidValue {
+                ref flag,
+                ref value,
+            } => write!(f, "invalid value `{value}` for flag `{flag}`\n{USAGE}"),
+            Self::InvalidModule {
+                ref module,
+                ref error,
+            } => write!(f, "invalid module `{module}`: {error}"),
+            Self::MissingModulus => write!(f, "the modulus has to be specified\n{USAGE}"),
+            Self::UnsupportedModulus(modulus) => write!(
+                f,
+                "modulus {modulus} is not supported, it should be between 2 and {MAX_MODULUS}"
+            ),
+            Self::RecursionTooSmall(recursion) => write!(
+                f,
+                "parameter of recursion cannot be less than 2, got {recursion}"
+            ),
+            Self::ConflictingFlags(flag, other_flag) => write!(
+                f,
+                "flags `{flag}` and `{other_flag}` cannot be used together\n{USAGE}"
+            ),
+            Self::RequiredFlag(flag, required) => {
+                write!(f, "flag `{flag}` requires `{required}`\n{USAGE}")
+            }
+            Self::InvalidJobFile {
+                line: Some(line),
+                ref reason,
+            } => write!(f, "invalid job file, line {line}: {reason}"),
+            Self::InvalidJobFile {
+                line: None,
+                ref reason,
+            } => write!(f, "invalid job file: {reason}"),
+            Self::FailedJobs(nof_failed) => {
+                write!(f, "{nof_failed} jobs failed, see the summary for details")
+            }
+            Self::InvalidResult {
+                ref file,
+                ref error,
+            } => write!(f, "invalid result {}, {error}", file.display()),
+            Self::Io(ref error) => write!(f, "{error}"),
+        }
+    }
+}
+
+// main prints the debug representation of the error it returns,
+// so it might as well be readable
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/* ## parsing */
+
+fn parse_value<T: FromStr>(flag: &str, maybe_value: Option<String>) -> Result<T, Error> {
+    let value = maybe_value.ok_or_else(|| Error::MissingValue(flag.to_owned()))?;
+    value.parse().map_err(|_err| Error::InvalidValue {
+        flag: flag.to_owned(),
+        value,
+    })
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "txt" => Ok(Self::Txt),
+            "debug" => Ok(Self::Debug),
+            "json" => Ok(Self::Json),
+            "pairs" => Ok(Self::Pairs),
+            "grid" => Ok(Self::Grid),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Command {
+    fn from_words(words: Vec<String>) -> Result<Self, Error> {
+        let mut remaining = words.into_iter();
+        let name = remaining.next().ok_or(Error::MissingCommand)?;
+        let maybe_command = match name.as_str() {
+            "category" => Some(Self::Category),
+            "hom" => remaining
+                .next()
+                .zip(remaining.next())
+                .map(|(source, target)| Self::Hom(source, target)),
+            "submodules" => remaining.next().map(Self::Submodules),
+            "szymczak" => Some(Self::Szymczak),
+            "szymczak-full" => Some(Self::SzymczakFull),
+            "batch" => remaining
+                .next()
+                .map(|job_file| Self::Batch(job_file.into())),
+            "convert" => remaining.next().map(|result| Self::Convert(result.into())),
+            _ => return Err(Error::UnknownCommand(name)),
+        };
+        match (maybe_command, remaining.next()) {
+            (Some(command), None) => Ok(command),
+            _ => Err(Error::WrongArguments(name)),
+        }
+    }
+}
+
+impl Config {
+    pub fn from_args<J: IntoIterator<Item = String>>(args: J) -> Result<Self, Error> {
+        let mut config = Self::default();
+        let mut modulus = None;
+        let mut format = None;
+        let mut words = Vec::new();
+        let mut remaining = args.into_iter();
+        while let Some(flag) = remaining.next() {
+            match flag.as_str() {
+                "--modulus" | "-n" => modulus = Some(parse_value(&flag, remaining.next())?),
+                "--max-dim" | "-d" => config.max_dim = parse_value(&flag, remaining.next())?,
+                "--recursion" | "-k" => config.recursion = parse_value(&flag, remaining.next())?,
+                "--endos" => config.endos = parse_value(&flag, remaining.next())?,
+                "--out" | "-o" => config.out = Some(parse_value(&flag, remaining.next())?),
+                "--format" | "-f" => format = Some(parse_value(&flag, remaining.next())?),
+                "--checkpoint" => config.checkpoint = Some(parse_value(&flag, remaining.next())?),
+                "--checkpoint-every" => {
+                    config.checkpoint_every =
+                        Duration::from_secs(parse_value(&flag, remaining.next())?);
+                }
+                "--resume" => config.resume = true,
+                "--lazy" => config.backend = config.backend.and(Backend::Lazy(None))?,
+                "--cache-limit" => {
+                    let limit = parse_value(&flag, remaining.next())?;
+                    config.backend = config.backend.and(Backend::Lazy(Some(limit)))?;
+                }
+                "--composition-tables" => {
+                    config.backend = config.backend.and(Backend::CompositionTables)?;
+                }
+                "--skeleton" => config.skeleton = true,
+                "--index" => config.index = true,
+                "--help" | "-h" => return Err(Error::Help),
+                _ if flag.starts_with('-') => return Err(Error::UnknownFlag(flag)),
+                _ => words.push(flag),
+            }
+        }
+        config.command = Command::from_words(words)?;
+        match config.command {
+            // the job file specifies the rings on its own
+            Command::Batch(_) => return Ok(config),
+            // results are already in the text format
+            Command::Convert(_) => {
+                config.format = format.unwrap_or(Format::Json);
+                return Ok(config);
+            }
+            _ => config.format = format.unwrap_or_default(),
+        }
+        config.modulus = modulus.ok_or(Error::MissingModulus)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if !(2..=MAX_MODULUS).contains(&self.modulus) {
+            return Err(Error::UnsupportedModulus(self.modulus));
+        }
+        if self.recursion < 2 {
+            return Err(Error::RecursionTooSmall(self.recursion));
+        }
+        // only the relations of a hom-set are written element by element
+        if matches!(self.format, Format::Pairs | Format::Grid)
+            && !matches!(self.command, Command::Hom(..))
+        {
+            return Err(Error::InvalidValue {
+                flag: "--format".to_owned(),
+                value: self.format.name().to_owned(),
+            });
+        }
+        // the morphisms of the tables cannot be restored without them
+        if self.backend == Backend::CompositionTables && self.checkpoint.is_some() {
+            return Err(Error::ConflictingFlags(
+                "--composition-tables",
+                "--checkpoint",
+            ));
+        }
+        // the index is saved next to the result, and the morphisms of the tables cannot be
+        if self.index && self.out.is_none() {
+            return Err(Error::RequiredFlag("--index", "--out"));
+        }
+        if self.backend == Backend::CompositionTables && self.index {
+            return Err(Error::ConflictingFlags("--composition-tables", "--index"));
+        }
+        Ok(())
+    }
+}
+
+/* # dispatch */
+
+/**
+the commands computing over the ring, the only ones instantiated for every modulus
+*/
+#[derive(Clone, Copy)]
+enum RingCommand<'config> {
+    Category,
+    Hom(&'config str, &'config str),
+    Submodules(&'config str),
+    Szymczak,
+    SzymczakFull,
+}
+
+pub fn execute(config: &Config) -> Result<(), Error> {
+    let command = match config.command {
+        Command::Batch(ref job_file) => return batch::run(job_file),
+        Command::Convert(ref result) => return pipeline::convert(config, result),
+        Command::Category => RingCommand::Category,
+        Command::Hom(ref source, ref target) => RingCommand::Hom(source, target),
+        Command::Submodules(ref module) => RingCommand::Submodules(module),
+        Command::Szymczak => RingCommand::Szymczak,
+        Command::SzymczakFull => RingCommand::SzymczakFull,
+    };
+    dispatch(config, command)
+}
+
+/**
+the ring is a type parameter of the whole computation,
+so every supported modulus gets its own instantiation of the pipeline
+and the one matching the configuration is picked at runtime.
+the table has to be kept in sync with `MAX_MODULUS`.
+*/
+#[cfg(not(test))]
+fn dispatch(config: &Config, command: RingCommand<'_>) -> Result<(), Error> {
+    seq!(P in 2..=62 {
+        match config.modulus {
+            #(
+                P => run::<typenum::U~P>(config, command),
+            )*
+            modulus => Err(Error::UnsupportedModulus(modulus)),
+        }
+    })
+}
+
+// every instantiation costs a lot of compile time and memory,
+// tests only need a handful of them
+#[cfg(test)]
+fn dispatch(config: &Config, command: RingCommand<'_>) -> Result<(), Error> {
+    seq!(P in 2..=7 {
+        match config.modulus {
+            #(
+                P => run::<typenum::U~P>(config, command),
+            )*
+            modulus => Err(Error::UnsupportedModulus(modulus)),
+        }
+    })
+}
+
+fn run<Period: Radix + IsGreater<U1> + Send + Sync>(
+    config: &Config,
+    command: RingCommand<'_>,
+) -> Result<(), Error> {
+    match command {
+        RingCommand::Category => pipeline::category::<Period>(config),
+        RingCommand::Hom(source, target) => pipeline::hom::<Period>(
+            config,
+            &Arc::new(pipeline::parse_module(source)?),
+            &Arc::new(pipeline::parse_module(target)?),
+        ),
+        RingCommand::Submodules(module) => {
+            pipeline::submodules::<Period>(config, &pipeline::parse_module(module)?)
+        }
+        RingCommand::Szymczak => pipeline::szymczak::<Period>(config),
+        RingCommand::SzymczakFull => pipeline::szymczak_full::<Period>(config),
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn parsing_arguments() {
+        let config = Config::from_args(args(
+            "szymczak-full --modulus 12 --max-dim 3 --recursion 4 --endos maps --out res/Z12 --format json",
+        ))
+        .expect("arguments are valid");
+        assert_eq!(
+            config,
+            Config {
+                command: Command::SzymczakFull,
+                modulus: 12,
+                max_dim: 3,
+                recursion: 4,
+                endos: EndoFilter::Maps,
+                out: Some(PathBuf::from("res/Z12")),
+                format: Format::Json,
+                checkpoint: None,
+                checkpoint_every: Duration::from_secs(DEFAULT_CHECKPOINT_SECONDS),
+                resume: false,
+                backend: Backend::Eager,
+                skeleton: false,
+                index: false,
+            }
+        );
+
+        let resumed = Config::from_args(args(
+            "szymczak -n 6 --checkpoint ckpt/Z6 --checkpoint-every 90 --resume",
+        ))
+        .expect("arguments are valid");
+        assert_eq!(resumed.checkpoint, Some(PathBuf::from("ckpt/Z6")));
+        assert_eq!(resumed.checkpoint_every, Duration::from_secs(90));
+        assert!(resumed.resume);
+
+        let lazy = Config::from_args(args("szymczak -n 6 --lazy")).expect("arguments are valid");
+        assert_eq!(lazy.backend, Backend::Lazy(None));
+        let limited = Config::from_args(args("szymczak -n 6 --cache-limit 1000"))
+            .expect("arguments are valid");
+        assert_eq!(
+            limited.backend,
+            Backend::Lazy(Some(1000)),
+            "a cache limit implies the lazy category"
+        );
+        let limited_lazy = Config::from_args(args("szymczak -n 6 --cache-limit 1000 --lazy"))
+            .expect("arguments are valid");
+        assert_eq!(
+            limited_lazy.backend,
+            Backend::Lazy(Some(1000)),
+            "the cache limit is kept by a later --lazy"
+        );
+
+        let skeletal =
+            Config::from_args(args("category -n 12 --skeleton")).expect("arguments are valid");
+        assert!(skeletal.skeleton);
+
+        let indexed = Config::from_args(args("szymczak -n 12 --index --out res/Z12"))
+            .expect("arguments are valid");
+        assert!(indexed.index);
+
+        let tabulated = Config::from_args(args("szymczak -n 12 --composition-tables"))
+            .expect("arguments are valid");
+        assert_eq!(tabulated.backend, Backend::CompositionTables);
+
+        let defaults = Config::from_args(args("szymczak -n 5")).expect("arguments are valid");
+        assert_eq!(defaults.max_dim, 2, "default dimension");
+        assert_eq!(defaults.recursion, 8, "default recursion parameter");
+        assert_eq!(defaults.out, None, "default output");
+        assert_eq!(defaults.format, Format::Txt, "default format");
+        assert_eq!(defaults.endos, EndoFilter::Wide, "default endomorphisms");
+
+        let hom = Config::from_args(args("-n 4 hom Z2xZ4 Z4")).expect("arguments are valid");
+        assert_eq!(
+            hom.command,
+            Command::Hom("Z2xZ4".to_owned(), "Z4".to_owned())
+        );
+        let grid =
+            Config::from_args(args("hom Z2 Z4 -n 4 --format grid")).expect("arguments are valid");
+        assert_eq!(grid.format, Format::Grid);
+
+        let submodules = Config::from_args(args("submodules 0 -n 4")).expect("arguments are valid");
+        assert_eq!(submodules.command, Command::Submodules("0".to_owned()));
+
+        let batch = Config::from_args(args("batch jobs/sweep.jobs")).expect("arguments are valid");
+        assert_eq!(
+            batch.command,
+            Command::Batch(PathBuf::from("jobs/sweep.jobs"))
+        );
+
+        let convert =
+            Config::from_args(args("convert results/Z4-dim-2")).expect("arguments are valid");
+        assert_eq!(
+            convert.command,
+            Command::Convert(PathBuf::from("results/Z4-dim-2"))
+        );
+        assert_eq!(convert.format, Format::Json, "default format of conversion");
+    }
+
+    #[test]
+    fn rejecting_arguments() {
+        assert!(matches!(
+            Config::from_args(args("szymczak --max-dim 2")),
+            Err(Error::MissingModulus)
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak --modulus 63")),
+            Err(Error::UnsupportedModulus(63))
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak --modulus 4 --recursion 1")),
+            Err(Error::RecursionTooSmall(1))
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak --modulus four")),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak --modulus 4 --format pdf")),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak --modulus 4 --format pairs")),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak --modulus 4 --endos some")),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak --modulus")),
+            Err(Error::MissingValue(_))
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak --dimension 2")),
+            Err(Error::UnknownFlag(_))
+        ));
+        assert!(matches!(
+            Config::from_args(args("--modulus 4")),
+            Err(Error::MissingCommand)
+        ));
+        assert!(matches!(
+            Config::from_args(args("leray --modulus 4")),
+            Err(Error::UnknownCommand(_))
+        ));
+        assert!(matches!(
+            Config::from_args(args("hom Z4 --modulus 4")),
+            Err(Error::WrongArguments(_))
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak -n 4 --composition-tables --cache-limit 100")),
+            Err(Error::ConflictingFlags(
+                "--composition-tables",
+                "--cache-limit"
+            ))
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak -n 4 --lazy --composition-tables")),
+            Err(Error::ConflictingFlags("--lazy", "--composition-tables"))
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak -n 4 --index")),
+            Err(Error::RequiredFlag("--index", "--out"))
+        ));
+        assert!(matches!(
+            Config::from_args(args("szymczak -n 4 --index --out res --composition-tables")),
+            Err(Error::ConflictingFlags("--composition-tables", "--index"))
+        ));
+    }
+}
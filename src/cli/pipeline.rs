@@ -0,0 +1,609 @@
+use crate::{
+    category::{
+        arena::ArenaCategory,
+        functors::{
+            index::ClassIndex,
+            szymczak::{SzymczakClasses, SzymczakClassesFull},
+            EndoFilter,
+        },
+        lazy::LazyCategory,
+        morphism::{Endo, Enumerable, IsBij, IsMap, IsMatching, IsWide},
+        object::{Classifiable, Object, PartiallyEnumerable},
+        relation::Relation,
+        Category, HomSets, PrettyName,
+    },
+    cli::{Backend, Config, Error, Format},
+    ralg::{
+        cgroup::{ideal::CIdeal, Radix, C},
+        module::canon::object::Object as Module,
+    },
+    results::text as text_results,
+    util::{
+        checkpoint::Checkpoint,
+        json::{Json, ToJson},
+    },
+};
+use std::{
+    fmt, fs,
+    hash::Hash,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use typenum::{IsGreater, U1};
+
+type Zn<Period> = Module<C<Period>, CIdeal<Period>>;
+type Rel<Period> = Relation<C<Period>, CIdeal<Period>>;
+
+/**
+what the szymczak stages need from the objects and the morphisms,
+whichever category they come from
+*/
+trait StageObject =
+    Object + Hash + Clone + Send + Sync + fmt::Debug + fmt::Display + ToJson + PrettyName;
+trait StageMorphism<O: Object> = Endo<O>
+    + IsWide<O>
+    + IsMap<O>
+    + IsBij<O>
+    + IsMatching<O>
+    + Send
+    + Sync
+    + fmt::Debug
+    + ToJson
+    + PrettyName;
+
+/* # output */
+
+enum Rendered {
+    Text(String),
+    Json(Json),
+}
+
+fn render<T: fmt::Display + fmt::Debug + ToJson>(thing: &T, format: Format) -> Rendered {
+    match format {
+        // only the relations are written element by element, see `render_relations`
+        Format::Txt | Format::Pairs | Format::Grid => Rendered::Text(format!("{thing}")),
+        Format::Debug => Rendered::Text(format!("{thing:?}\n")),
+        Format::Json => Rendered::Json(thing.to_json()),
+    }
+}
+
+fn render_all<H: fmt::Display + ToJson, T: fmt::Display + fmt::Debug + ToJson>(
+    header: &[(&str, &H)],
+    things: &[T],
+    format: Format,
+) -> Rendered {
+    match format {
+        Format::Txt | Format::Pairs | Format::Grid => Rendered::Text(format!(
+            "{}Number of elements: {}\n===\n{}",
+            header
+                .iter()
+                .map(|&(key, value)| format!("{key}: {value}\n"))
+                .collect::<Vec<_>>()
+                .concat(),
+            things.len(),
+            things
+                .iter()
+                .map(|thing| format!("{thing}\n"))
+                .collect::<Vec<_>>()
+                .concat()
+        )),
+        Format::Debug => Rendered::Text(format!("{things:?}\n")),
+        Format::Json => Rendered::Json(Json::Object(
+            header
+                .iter()
+                .map(|&(key, value)| (key.to_lowercase(), value.to_json()))
+                .chain([("elements".to_owned(), things.to_json())])
+                .collect(),
+        )),
+    }
+}
+
+/**
+the pairs are written one relation per line, and the grids are separated by empty lines
+*/
+fn render_relations<Period: Radix + IsGreater<U1>>(
+    header: &[(&str, &Zn<Period>)],
+    relations: &[Rel<Period>],
+    format: Format,
+) -> Rendered {
+    let preamble = header
+        .iter()
+        .map(|&(key, value)| format!("{key}: {value}\n"))
+        .collect::<Vec<_>>()
+        .concat();
+    match format {
+        Format::Pairs => Rendered::Text(format!(
+            "{preamble}Number of elements: {}\n===\n{}",
+            relations.len(),
+            relations
+                .iter()
+                .map(|relation| format!("{}\n", relation.pairs()))
+                .collect::<Vec<_>>()
+                .concat()
+        )),
+        Format::Grid => Rendered::Text(format!(
+            "{preamble}Number of elements: {}\n===\n{}",
+            relations.len(),
+            relations
+                .iter()
+                .map(|relation| relation.grid().to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+        Format::Txt | Format::Debug | Format::Json => render_all(header, relations, format),
+    }
+}
+
+/**
+the text format ends with the timings of the stages, as the plotting scripts expect,
+and json puts them next to the result. the debug format carries the result only.
+*/
+fn report(
+    config: &Config,
+    rendered: Rendered,
+    timings: &[(&str, Duration)],
+    recursion: Option<usize>,
+    duplicate_objects_removed: Option<usize>,
+) -> String {
+    match rendered {
+        Rendered::Text(text) if config.format == Format::Txt => format!(
+            "{text}===\n{}{}{}",
+            timings
+                .iter()
+                .map(|&(stage, elapsed)| format!("{stage} after: {}\n", elapsed.as_secs_f64()))
+                .collect::<Vec<_>>()
+                .concat(),
+            recursion.map_or_else(String::new, |parameter| format!(
+                "Parameter of the recursion: {parameter}\n"
+            )),
+            duplicate_objects_removed.map_or_else(String::new, |duplicates| format!(
+                "Duplicate objects removed: {duplicates}\n"
+            ))
+        ),
+        Rendered::Text(text) => text,
+        Rendered::Json(result) => {
+            let mut entries = vec![
+                ("result".to_owned(), result),
+                (
+                    "timings".to_owned(),
+                    Json::Object(
+                        timings
+                            .iter()
+                            .map(|&(stage, elapsed)| {
+                                (stage.to_owned(), Json::Float(elapsed.as_secs_f64()))
+                            })
+                            .collect(),
+                    ),
+                ),
+            ];
+            if let Some(parameter) = recursion {
+                entries.push(("recursion".to_owned(), parameter.to_json()));
+            }
+            if let Some(duplicates) = duplicate_objects_removed {
+                entries.push(("duplicate_objects_removed".to_owned(), duplicates.to_json()));
+            }
+            format!("{}\n", Json::Object(entries))
+        }
+    }
+}
+
+/**
+writes to the file given by `--out`, creating the missing directories,
+or to the standard output if there is none.
+the file is written under a temporary name first and renamed afterwards,
+so an existing output is always a complete one.
+*/
+fn emit(config: &Config, contents: &str) -> io::Result<()> {
+    if let Some(ref path) = config.out {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut partial = path.clone().into_os_string();
+        partial.push(".part");
+        fs::write(&partial, contents)?;
+        fs::rename(partial, path)
+    } else {
+        print!("{contents}");
+        Ok(())
+    }
+}
+
+/* # checkpoints */
+
+const CATEGORY_CHECKPOINT: &str = "category.checkpoint";
+const CLASSES_CHECKPOINT: &str = "classes.checkpoint";
+
+/**
+the filter is a part of the tag unless it is the default one,
+so the checkpoints written before it could be chosen are still resumed
+*/
+fn checkpoint(config: &Config, name: &str) -> Option<Checkpoint> {
+    config.checkpoint.as_ref().map(|directory| {
+        Checkpoint::new(
+            directory.join(name),
+            match config.endos == EndoFilter::default() {
+                true => format!("Z{} dim {}", config.modulus, config.max_dim),
+                false => format!(
+                    "Z{} dim {} {} endomorphisms",
+                    config.modulus,
+                    config.max_dim,
+                    config.endos.name()
+                ),
+            },
+            config.checkpoint_every,
+            config.resume,
+        )
+    })
+}
+
+/**
+one object of every isomorphism type with `--skeleton`,
+together with the number of the other ones left out
+*/
+fn objects<Period: Radix + IsGreater<U1>>(config: &Config) -> (Vec<Zn<Period>>, Option<usize>) {
+    let all_objects = Zn::<Period>::all_by_dimension(0..=config.max_dim.into());
+    if config.skeleton {
+        let (objects, nof_duplicates) = Zn::<Period>::skeleton(all_objects);
+        (objects, Some(nof_duplicates))
+    } else {
+        (all_objects.collect(), None)
+    }
+}
+
+fn generate_category<Period: Radix + IsGreater<U1> + Send + Sync>(
+    config: &Config,
+    objects: Vec<Zn<Period>>,
+) -> Result<Category<Zn<Period>, Rel<Period>>, Error> {
+    Ok(match checkpoint(config, CATEGORY_CHECKPOINT) {
+        Some(ref checkpoint) => Category::new_checkpointed(objects, checkpoint)?,
+        None => Category::from_objects(objects),
+    })
+}
+
+fn generate_classes<
+    Period: Radix + IsGreater<U1> + Send + Sync,
+    Cat: HomSets<Zn<Period>, Rel<Period>>,
+>(
+    config: &Config,
+    category: &Cat,
+) -> Result<SzymczakClasses<Zn<Period>, Rel<Period>>, Error> {
+    let classes = match checkpoint(config, CLASSES_CHECKPOINT) {
+        Some(ref checkpoint) => SzymczakClasses::functor_checkpointed(
+            category,
+            config.recursion,
+            config.endos,
+            checkpoint,
+        )?,
+        None => SzymczakClasses::functor_filtered(category, config.recursion, config.endos),
+    };
+    if let Some(path) = index_path(config) {
+        ClassIndex::new(&classes).save(&path, &format!("Z{}", config.modulus))?;
+    }
+    Ok(classes)
+}
+
+/**
+the index of the classes goes next to the result, with `.index` appended to its name
+*/
+fn index_path(config: &Config) -> Option<PathBuf> {
+    config.out.as_ref().filter(|_out| config.index).map(|out| {
+        let mut path = out.clone().into_os_string();
+        path.push(".index");
+        PathBuf::from(path)
+    })
+}
+
+// the handles of an arena mean nothing without it, so its classes are not checkpointed
+#[allow(
+    clippy::unnecessary_wraps,
+    reason = "it stands in for `generate_classes`"
+)]
+fn generate_classes_in_arena<O: StageObject, M: StageMorphism<O>, Cat: HomSets<O, M>>(
+    config: &Config,
+    category: &Cat,
+) -> Result<SzymczakClasses<O, M>, Error> {
+    Ok(SzymczakClasses::functor_filtered(
+        category,
+        config.recursion,
+        config.endos,
+    ))
+}
+
+/**
+once the result is written, its checkpoints are of no use
+*/
+fn remove_checkpoints(config: &Config) -> io::Result<()> {
+    for name in [CATEGORY_CHECKPOINT, CLASSES_CHECKPOINT] {
+        if let Some(checkpoint) = checkpoint(config, name) {
+            checkpoint.remove()?;
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_module<Period: Radix + IsGreater<U1>>(module: &str) -> Result<Zn<Period>, Error> {
+    module.parse().map_err(|error| Error::InvalidModule {
+        module: module.to_owned(),
+        error,
+    })
+}
+
+/* # stages */
+
+pub fn category<Period: Radix + IsGreater<U1> + Send + Sync>(config: &Config) -> Result<(), Error> {
+    let category_time = Instant::now();
+    let (objects, duplicate_objects_removed) = objects::<Period>(config);
+    let category = generate_category::<Period>(config, objects)?;
+    let category_time_elapsed = category_time.elapsed();
+
+    emit(
+        config,
+        &report(
+            config,
+            render(&category, config.format),
+            &[("Category generated", category_time_elapsed)],
+            None,
+            duplicate_objects_removed,
+        ),
+    )?;
+    remove_checkpoints(config)?;
+    Ok(())
+}
+
+pub fn hom<Period: Radix + IsGreater<U1> + Send + Sync>(
+    config: &Config,
+    source: &Arc<Zn<Period>>,
+    target: &Arc<Zn<Period>>,
+) -> Result<(), Error> {
+    let hom_time = Instant::now();
+    let relations: Vec<_> = Rel::<Period>::hom(Arc::clone(source), Arc::clone(target)).collect();
+    let hom_time_elapsed = hom_time.elapsed();
+
+    emit(
+        config,
+        &report(
+            config,
+            render_relations(
+                &[("Source", &**source), ("Target", &**target)],
+                &relations,
+                config.format,
+            ),
+            &[("Relations generated", hom_time_elapsed)],
+            None,
+            None,
+        ),
+    )?;
+    Ok(())
+}
+
+pub fn submodules<Period: Radix + IsGreater<U1> + Send + Sync>(
+    config: &Config,
+    module: &Zn<Period>,
+) -> Result<(), Error> {
+    let submodules_time = Instant::now();
+    let submodules = module.clone().submodules();
+    let submodules_time_elapsed = submodules_time.elapsed();
+
+    emit(
+        config,
+        &report(
+            config,
+            render_all(&[("Module", module)], &submodules, config.format),
+            &[("Submodules generated", submodules_time_elapsed)],
+            None,
+            None,
+        ),
+    )?;
+    Ok(())
+}
+
+/**
+what is computed from the category once it is built, whichever backend holds it
+*/
+trait SzymczakStage {
+    fn run<
+        O: StageObject,
+        M: StageMorphism<O>,
+        Cat: HomSets<O, M>,
+        F: FnOnce(&Config, &Cat) -> Result<SzymczakClasses<O, M>, Error>,
+    >(
+        config: &Config,
+        category: &Cat,
+        generate_classes: F,
+        category_time_elapsed: Duration,
+        duplicate_objects_removed: Option<usize>,
+    ) -> Result<(), Error>;
+}
+
+struct Classes;
+
+impl SzymczakStage for Classes {
+    fn run<
+        O: StageObject,
+        M: StageMorphism<O>,
+        Cat: HomSets<O, M>,
+        F: FnOnce(&Config, &Cat) -> Result<SzymczakClasses<O, M>, Error>,
+    >(
+        config: &Config,
+        category: &Cat,
+        generate_classes: F,
+        category_time_elapsed: Duration,
+        duplicate_objects_removed: Option<usize>,
+    ) -> Result<(), Error> {
+        let szymczak_classes_time = Instant::now();
+        let szymczak_classes = generate_classes(config, category)?;
+        let szymczak_classes_time_elapsed = szymczak_classes_time.elapsed();
+
+        emit(
+            config,
+            &report(
+                config,
+                render(&szymczak_classes, config.format),
+                &[
+                    ("Category generated", category_time_elapsed),
+                    (
+                        "Isomorphisms classes generated",
+                        szymczak_classes_time_elapsed,
+                    ),
+                ],
+                Some(config.recursion),
+                duplicate_objects_removed,
+            ),
+        )?;
+        remove_checkpoints(config)?;
+        Ok(())
+    }
+}
+
+struct ClassesFull;
+
+impl SzymczakStage for ClassesFull {
+    fn run<
+        O: StageObject,
+        M: StageMorphism<O>,
+        Cat: HomSets<O, M>,
+        F: FnOnce(&Config, &Cat) -> Result<SzymczakClasses<O, M>, Error>,
+    >(
+        config: &Config,
+        category: &Cat,
+        generate_classes: F,
+        category_time_elapsed: Duration,
+        duplicate_objects_removed: Option<usize>,
+    ) -> Result<(), Error> {
+        let szymczak_classes_time = Instant::now();
+        let szymczak_classes = generate_classes(config, category)?;
+        let szymczak_classes_time_elapsed = szymczak_classes_time.elapsed();
+
+        let szymczak_classes_full_time = Instant::now();
+        let szymczak_classes_full = SzymczakClassesFull::all_isos(szymczak_classes, category);
+        let szymczak_classes_full_time_elapsed = szymczak_classes_full_time.elapsed();
+
+        emit(
+            config,
+            &report(
+                config,
+                render(&szymczak_classes_full, config.format),
+                &[
+                    ("Category generated", category_time_elapsed),
+                    (
+                        "Isomorphisms classes generated",
+                        szymczak_classes_time_elapsed,
+                    ),
+                    ("All isomorphisms added", szymczak_classes_full_time_elapsed),
+                ],
+                Some(config.recursion),
+                duplicate_objects_removed,
+            ),
+        )?;
+        remove_checkpoints(config)?;
+        Ok(())
+    }
+}
+
+/**
+builds the category with the backend of the configuration and runs the stage on it
+*/
+fn szymczak_stage<Period: Radix + IsGreater<U1> + Send + Sync, S: SzymczakStage>(
+    config: &Config,
+) -> Result<(), Error> {
+    let category_time = Instant::now();
+    let (objects, duplicate_objects_removed) = objects::<Period>(config);
+    match config.backend {
+        Backend::Lazy(cache_limit) => {
+            // the hom-sets are generated along with the classes, so this times the objects only
+            let category = LazyCategory::from_objects(objects, cache_limit);
+            let category_time_elapsed = category_time.elapsed();
+            S::run(
+                config,
+                &category,
+                generate_classes::<Period, _>,
+                category_time_elapsed,
+                duplicate_objects_removed,
+            )
+        }
+        Backend::CompositionTables => {
+            let category = ArenaCategory::with_composition_tables(&generate_category::<Period>(
+                config, objects,
+            )?);
+            let category_time_elapsed = category_time.elapsed();
+            S::run(
+                config,
+                &category,
+                generate_classes_in_arena,
+                category_time_elapsed,
+                duplicate_objects_removed,
+            )
+        }
+        Backend::Eager => {
+            let category = generate_category::<Period>(config, objects)?;
+            let category_time_elapsed = category_time.elapsed();
+            S::run(
+                config,
+                &category,
+                generate_classes::<Period, _>,
+                category_time_elapsed,
+                duplicate_objects_removed,
+            )
+        }
+    }
+}
+
+pub fn szymczak<Period: Radix + IsGreater<U1> + Send + Sync>(config: &Config) -> Result<(), Error> {
+    szymczak_stage::<Period, Classes>(config)
+}
+
+pub fn szymczak_full<Period: Radix + IsGreater<U1> + Send + Sync>(
+    config: &Config,
+) -> Result<(), Error> {
+    szymczak_stage::<Period, ClassesFull>(config)
+}
+
+/**
+the timings of the original run are carried over,
+so the converted file holds the same information as the text one
+*/
+pub fn convert(config: &Config, result: &Path) -> Result<(), Error> {
+    let record = text_results::parse(&fs::read_to_string(result)?).map_err(|error| {
+        Error::InvalidResult {
+            file: result.to_path_buf(),
+            error,
+        }
+    })?;
+    let timings: Vec<(&str, Duration)> = record
+        .trailer()
+        .timings
+        .iter()
+        .map(|&(ref stage, seconds)| {
+            (
+                stage.as_str(),
+                Duration::try_from_secs_f64(seconds).unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    let rendered = match config.format {
+        Format::Json => Rendered::Json(record.to_json()),
+        Format::Debug => Rendered::Text(format!("{record:?}\n")),
+        // writing the text again would only reproduce the input,
+        // and the results hold no relations to be written element by element
+        Format::Txt | Format::Pairs | Format::Grid => {
+            return Err(Error::InvalidValue {
+                flag: "--format".to_owned(),
+                value: config.format.name().to_owned(),
+            })
+        }
+    };
+    emit(
+        config,
+        &report(
+            config,
+            rendered,
+            &timings,
+            record.trailer().recursion,
+            record.trailer().duplicate_objects_removed,
+        ),
+    )?;
+    Ok(())
+}
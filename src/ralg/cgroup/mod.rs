@@ -1,10 +1,13 @@
-use crate::ralg::{
-    ring::{
-        AdditiveGroup, AdditiveMonoid, AdditivePartialGroup, AdditivePartialMonoid,
-        Bezout as BezoutRing, Demesne, Enumerable, Factorial as FactorialRing,
-        MultiplicativeMonoid, MultiplicativePartialMonoid, Ring,
+use crate::{
+    ralg::{
+        ring::{
+            AdditiveGroup, AdditiveMonoid, AdditivePartialGroup, AdditivePartialMonoid,
+            Bezout as BezoutRing, Demesne, Enumerable, Factorial as FactorialRing,
+            MultiplicativeMonoid, MultiplicativePartialMonoid, Ring,
+        },
+        util::{extended_euclid, try_inverse},
     },
-    util::{extended_euclid, try_inverse},
+    util::json::{Json, ToJson},
 };
 use std::{fmt, hash::Hash, marker};
 use typenum::{IsGreater, NonZero, Unsigned, U1};
@@ -62,6 +65,12 @@ impl<Period: Radix> fmt::Display for C<Period> {
     }
 }
 
+impl<Period: Radix> ToJson for C<Period> {
+    fn to_json(&self) -> Json {
+        self.raw.to_json()
+    }
+}
+
 /* ## send and sync */
 
 unsafe impl<Period: Radix + Send> Send for C<Period> {}
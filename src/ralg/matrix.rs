@@ -1,9 +1,12 @@
-use crate::ralg::{
-    module::canon::element::Element as CanonElement,
-    ring::{
-        ideal::Ideal, AdditivePartialGroup, AdditivePartialMonoid, Bezout as BezoutRing, Demesne,
-        Ring,
+use crate::{
+    ralg::{
+        module::canon::element::Element as CanonElement,
+        ring::{
+            ideal::Ideal, AdditivePartialGroup, AdditivePartialMonoid, Bezout as BezoutRing,
+            Demesne, Ring,
+        },
     },
+    util::json::{Json, ToJson},
 };
 use itertools::Itertools;
 use std::{cmp, collections::BTreeSet, fmt};
@@ -46,6 +49,19 @@ impl<T: fmt::Display> fmt::Display for VecD2<T> {
     }
 }
 
+/**
+the matrix as the array of its rows
+*/
+impl<T: ToJson> ToJson for VecD2<T> {
+    fn to_json(&self) -> Json {
+        Json::Array(
+            self.rows()
+                .map(|row| Json::Array(row.map(ToJson::to_json).collect()))
+                .collect(),
+        )
+    }
+}
+
 /* ## contianer operations */
 
 impl<T> VecD2<T> {
@@ -331,7 +347,7 @@ impl<R: Ring> AdditivePartialGroup for Matrix<R> {
 /* ## matrix operations */
 
 impl<R: Ring> Matrix<R> {
-    fn identity(nof_cols: usize, nof_rows: usize) -> Self {
+    pub fn identity(nof_cols: usize, nof_rows: usize) -> Self {
         Self::from_buffer(
             (0..nof_rows).flat_map(|r| {
                 (0..nof_cols).map(move |c| match r == c {
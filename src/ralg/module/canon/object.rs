@@ -1,8 +1,8 @@
 use crate::{
     category::{
         object::{
-            Concrete as ConcreteObject, Duplicable as DuplicableObject,
-            Enumerable as EnumerableObject, Object as CatObject,
+            Classifiable as ClassifiableObject, Concrete as ConcreteObject,
+            Duplicable as DuplicableObject, Enumerable as EnumerableObject, Object as CatObject,
             PartiallyEnumerable as PartiallyEnumerableObject,
         },
         PrettyName,
@@ -22,9 +22,13 @@ use crate::{
             Factorial as FactorialRing, MultiplicativeMonoid, MultiplicativePartialMonoid, Ring,
         },
     },
+    util::{
+        checkpoint::Persistent,
+        json::{Json, ToJson},
+    },
 };
 use itertools::Itertools;
-use std::{collections::BTreeSet, fmt, sync::Arc};
+use std::{collections::BTreeSet, fmt, str::FromStr, sync::Arc};
 use typenum::{IsGreater, Unsigned, U1};
 
 /* # torsion coefficients object */
@@ -134,6 +138,96 @@ impl<Period: Radix + IsGreater<U1>> fmt::Display for Object<C<Period>, CIdeal<Pe
     }
 }
 
+impl<Period: Radix + IsGreater<U1>> ToJson for Object<C<Period>, CIdeal<Period>> {
+    fn to_json(&self) -> Json {
+        Json::Array(
+            self.buffer
+                .iter()
+                .map(|mark| u16::from(mark.thing.ideal.generator()).to_json())
+                .collect(),
+        )
+    }
+}
+
+/* ## parsing */
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidCoefficient(String),
+    NotADivisor { coefficient: u16, modulus: u16 },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::InvalidCoefficient(ref summand) => {
+                write!(f, "`{summand}` is not of the form `Zk` for a number k")
+            }
+            Self::NotADivisor {
+                coefficient,
+                modulus,
+            } => write!(f, "{coefficient} does not divide the modulus {modulus}"),
+        }
+    }
+}
+
+/**
+inverse of the display, so `0`, `Z4` and `Z2xZ4` are all accepted.
+coefficients which are not powers of primes are split as usual,
+so over `Z6` the string `Z6` is read as `Z3xZ2`.
+*/
+impl<Period: Radix + IsGreater<U1>> FromStr for Object<C<Period>, CIdeal<Period>> {
+    type Err = ParseError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string.trim() {
+            "0" => Ok(Self::default()),
+            summands => summands
+                .split('x')
+                .map(|summand| {
+                    let coefficient: u16 = summand
+                        .trim()
+                        .strip_prefix('Z')
+                        .and_then(|coefficient| coefficient.parse().ok())
+                        .ok_or_else(|| ParseError::InvalidCoefficient(summand.to_owned()))?;
+                    match Period::U16.checked_rem(coefficient) {
+                        Some(0) => Ok(coefficient),
+                        _ => Err(ParseError::NotADivisor {
+                            coefficient,
+                            modulus: Period::U16,
+                        }),
+                    }
+                })
+                .collect::<Result<Vec<u16>, _>>()
+                .map(|coefficients| coefficients.into_iter().collect()),
+        }
+    }
+}
+
+/* ## checkpoints */
+
+/**
+a module is stored as its torsion coefficients,
+the same ones as in the json
+*/
+impl<Period: Radix + IsGreater<U1>> Persistent for Object<C<Period>, CIdeal<Period>> {
+    fn persist(&self, bytes: &mut Vec<u8>) {
+        self.buffer
+            .iter()
+            .map(|mark| u16::from(mark.thing.ideal.generator()))
+            .collect::<Vec<u16>>()
+            .persist(bytes);
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Self> {
+        let coefficients = Vec::<u16>::restore(bytes)?;
+        coefficients
+            .iter()
+            .all(|&coefficient| Period::U16.checked_rem(coefficient) == Some(0))
+            .then(|| coefficients.into_iter().collect())
+    }
+}
+
 impl<Period: Radix + IsGreater<U1> + Unsigned> PrettyName for Object<C<Period>, CIdeal<Period>> {
     const PRETTY_NAME: &'static str = "Zn-Module";
 }
@@ -340,6 +434,21 @@ impl<R: Ring + Copy, I: Ideal<Parent = R> + Ord> DuplicableObject for Object<R,
     }
 }
 
+/**
+the torsion coefficients are sorted, so that the order of the summands does not matter
+*/
+impl<R: Ring + Copy + Into<u16>, I: PrincipalIdeal<Parent = R> + Ord> ClassifiableObject
+    for Object<R, I>
+{
+    type IsomorphismType = Vec<u16>;
+
+    fn isomorphism_type(&self) -> Self::IsomorphismType {
+        let mut coefficients: Vec<u16> = self.torsion_coeffs_as_u16().collect();
+        coefficients.sort_unstable();
+        coefficients
+    }
+}
+
 /* ### sub and quot structures */
 
 impl<Period: Radix + IsGreater<U1> + Send + Sync> Object<C<Period>, CIdeal<Period>> {
@@ -491,6 +600,35 @@ mod test {
         assert_eq!(marks.next(), None);
     }
 
+    #[test]
+    fn parsing() {
+        type R = C<U36>;
+        type I = CIdeal<U36>;
+
+        let module: Object<R, I> = "Z2xZ4x Z9".parse().expect("coefficients divide 36");
+        assert_eq!(module, Object::from_iter([2, 4, 9]));
+        assert_eq!(module.to_string().parse::<Object<R, I>>(), Ok(module));
+        assert_eq!("0".parse::<Object<R, I>>(), Ok(Object::default()));
+        assert_eq!("Z6".parse::<Object<R, I>>(), Ok(Object::from_iter([2, 3])));
+
+        assert_eq!(
+            "Z2xZ8".parse::<Object<R, I>>(),
+            Err(ParseError::NotADivisor {
+                coefficient: 8,
+                modulus: 36
+            })
+        );
+        for invalid in ["Z2xx", "6", "ZZ6", "ZZZ2xZ3", "Z2x3"] {
+            assert!(
+                matches!(
+                    invalid.parse::<Object<R, I>>(),
+                    Err(ParseError::InvalidCoefficient(_))
+                ),
+                "{invalid}"
+            );
+        }
+    }
+
     #[test]
     fn splitting() {
         type R = C<U64>;
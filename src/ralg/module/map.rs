@@ -17,6 +17,7 @@ use crate::{
             Factorial as FactorialRing, Ring,
         },
     },
+    util::json::{Json, ToJson},
 };
 use itertools::Itertools;
 use std::{fmt, sync::Arc};
@@ -45,6 +46,11 @@ impl<R: Ring + Copy, I: Ideal<Parent = R> + Ord> CanonToCanon<R, I> {
         }
     }
 
+    pub fn identity(object: &Arc<CanonModule<R, I>>) -> Self {
+        let dim = object.dimension();
+        Self::new(object, object, Matrix::identity(dim, dim))
+    }
+
     pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &R>> + '_ {
         self.matrix.rows()
     }
@@ -81,6 +87,19 @@ where
     }
 }
 
+impl<R: Ring + ToJson, I: Ideal<Parent = R> + Ord> ToJson for CanonToCanon<R, I>
+where
+    CanonModule<R, I>: ToJson,
+{
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("source", self.source.to_json()),
+            ("target", self.target.to_json()),
+            ("matrix", self.matrix.to_json()),
+        ])
+    }
+}
+
 /* ## equality */
 
 impl<R: Ring + Copy, I: Ideal<Parent = R> + Ord> PartialEq for CanonToCanon<R, I> {
@@ -117,7 +136,8 @@ impl<R: Ring + Copy, I: Ideal<Parent = R> + Ord> Morphism<CanonModule<R, I>>
     }
 
     fn try_compose(&self, other: &Self) -> Option<Self> {
-        (self.target == other.source).then_some(Self {
+        // lazily, composing the matrices of morphisms which do not match panics
+        (self.target == other.source).then(|| Self {
             source: Arc::clone(&self.source),
             target: Arc::clone(&other.target),
             matrix: self.matrix.compose(&other.matrix),
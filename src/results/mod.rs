@@ -0,0 +1,277 @@
+use crate::util::json::{Json, ToJson};
+use bitvec::vec::BitVec;
+
+pub mod text;
+
+/* # records of the results */
+
+/**
+the results as read back from the files, without the ring being known.
+a module is given by its torsion coefficients, in the order they were written.
+*/
+pub type ModuleRecord = Vec<u16>;
+
+fn cardinality(module: &ModuleRecord) -> Option<usize> {
+    module.iter().try_fold(1_usize, |product, &coefficient| {
+        product.checked_mul(usize::from(coefficient))
+    })
+}
+
+/**
+bits are in the order of the debug representation of the relation:
+for every element of the source, whether it is related to the consecutive elements of the target
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RelationRecord {
+    pub source: ModuleRecord,
+    pub target: ModuleRecord,
+    pub bits: BitVec,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Preamble {
+    pub functor: String,
+    pub object: String,
+    pub morphism: String,
+    // older results classify the wide endomorphisms without saying so
+    pub endomorphism_filter: Option<String>,
+    pub number_of_classes: usize,
+    // the fields below are written only for the classes without the isomorphisms
+    pub number_of_endomorphisms: Option<usize>,
+    pub every_class_has_a_map: Option<bool>,
+    pub every_class_has_a_bijection: Option<bool>,
+    pub every_class_has_exactly_one_bijection: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trailer {
+    pub timings: Vec<(String, f64)>,
+    pub recursion: Option<usize>,
+    pub duplicate_objects_removed: Option<usize>,
+}
+
+/* ## iso classes */
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectRecord {
+    pub object: ModuleRecord,
+    pub endomorphisms: Vec<RelationRecord>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassesRecord {
+    pub preamble: Preamble,
+    pub classes: Vec<Vec<ObjectRecord>>,
+    pub trailer: Trailer,
+}
+
+/* ## iso classes with all the isomorphisms */
+
+/**
+`phi` goes from the left endomorphism to the right one and `psi` back,
+`psi phi left^k = left^l` and `phi psi right^k' = right^l'`
+for `left_powers = (k, l)` and `right_powers = (k', l')`.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IsoRecord {
+    pub phi: RelationRecord,
+    pub psi: RelationRecord,
+    pub left_powers: (usize, usize),
+    pub right_powers: (usize, usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PairRecord {
+    pub left: RelationRecord,
+    pub left_cycle_length: usize,
+    pub right: RelationRecord,
+    pub right_cycle_length: usize,
+    pub isos: Vec<IsoRecord>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FullClassesRecord {
+    pub preamble: Preamble,
+    pub classes: Vec<Vec<PairRecord>>,
+    pub trailer: Trailer,
+}
+
+/* ## any result */
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Record {
+    Classes(ClassesRecord),
+    FullClasses(FullClassesRecord),
+}
+
+impl Record {
+    pub const fn trailer(&self) -> &Trailer {
+        match *self {
+            Self::Classes(ref record) => &record.trailer,
+            Self::FullClasses(ref record) => &record.trailer,
+        }
+    }
+}
+
+/* # conversion */
+
+// the schema is the one of the results themselves, see `util::json::ToJson`
+
+impl ToJson for RelationRecord {
+    fn to_json(&self) -> Json {
+        let row_length = cardinality(&self.target).unwrap_or(1).max(1);
+        Json::object([
+            ("source", self.source.to_json()),
+            ("target", self.target.to_json()),
+            (
+                "matrix",
+                Json::Array(
+                    self.bits
+                        .chunks(row_length)
+                        .map(|row| {
+                            Json::String(
+                                row.iter()
+                                    .map(|bit| match *bit {
+                                        true => '1',
+                                        false => '0',
+                                    })
+                                    .collect(),
+                            )
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+impl ToJson for ObjectRecord {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("object", self.object.to_json()),
+            ("endomorphisms", self.endomorphisms.to_json()),
+        ])
+    }
+}
+
+impl ToJson for IsoRecord {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("phi", self.phi.to_json()),
+            ("psi", self.psi.to_json()),
+            ("left_powers", self.left_powers.to_json()),
+            ("right_powers", self.right_powers.to_json()),
+        ])
+    }
+}
+
+impl ToJson for PairRecord {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("left", self.left.to_json()),
+            ("left_cycle_length", self.left_cycle_length.to_json()),
+            ("right", self.right.to_json()),
+            ("right_cycle_length", self.right_cycle_length.to_json()),
+            ("isos", self.isos.to_json()),
+        ])
+    }
+}
+
+/**
+objects of a class are sorted and the classes are given their positions as ids
+as in the json of the computed classes, so the two can be compared directly
+*/
+impl ToJson for ClassesRecord {
+    fn to_json(&self) -> Json {
+        let preamble = &self.preamble;
+        let classes = self
+            .classes
+            .iter()
+            .enumerate()
+            .map(|(position, class)| {
+                let mut objects: Vec<(String, Json)> = class
+                    .iter()
+                    .map(|object| (object.object.to_json().to_string(), object.to_json()))
+                    .collect();
+                objects.sort_by(|left, right| left.0.cmp(&right.0));
+                Json::object([
+                    ("id", position.to_json()),
+                    (
+                        "objects",
+                        Json::Array(objects.into_iter().map(|object| object.1).collect()),
+                    ),
+                ])
+            })
+            .collect();
+
+        Json::object([
+            ("functor", preamble.functor.to_json()),
+            ("object", preamble.object.to_json()),
+            ("morphism", preamble.morphism.to_json()),
+            (
+                "endomorphism_filter",
+                preamble
+                    .endomorphism_filter
+                    .as_ref()
+                    .map_or(Json::Null, ToJson::to_json),
+            ),
+            (
+                "number_of_endomorphisms",
+                preamble
+                    .number_of_endomorphisms
+                    .map_or(Json::Null, |number| number.to_json()),
+            ),
+            ("number_of_classes", preamble.number_of_classes.to_json()),
+            (
+                "every_class_has_a_map",
+                preamble
+                    .every_class_has_a_map
+                    .map_or(Json::Null, Json::Bool),
+            ),
+            (
+                "every_class_has_a_bijection",
+                preamble
+                    .every_class_has_a_bijection
+                    .map_or(Json::Null, Json::Bool),
+            ),
+            (
+                "every_class_has_exactly_one_bijection",
+                preamble
+                    .every_class_has_exactly_one_bijection
+                    .map_or(Json::Null, Json::Bool),
+            ),
+            ("classes", Json::Array(classes)),
+        ])
+    }
+}
+
+impl ToJson for FullClassesRecord {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("functor", self.preamble.functor.to_json()),
+            ("object", self.preamble.object.to_json()),
+            ("morphism", self.preamble.morphism.to_json()),
+            (
+                "endomorphism_filter",
+                self.preamble
+                    .endomorphism_filter
+                    .as_ref()
+                    .map_or(Json::Null, ToJson::to_json),
+            ),
+            (
+                "number_of_classes",
+                self.preamble.number_of_classes.to_json(),
+            ),
+            ("classes", self.classes.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Record {
+    fn to_json(&self) -> Json {
+        match *self {
+            Self::Classes(ref record) => record.to_json(),
+            Self::FullClasses(ref record) => record.to_json(),
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use crate::results::{
+    cardinality, ClassesRecord, FullClassesRecord, IsoRecord, ModuleRecord, ObjectRecord,
+    PairRecord, Preamble, Record, RelationRecord, Trailer,
+};
+use bitvec::vec::BitVec;
+use std::{fmt, str::FromStr};
+
+/* # errors */
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+fn error<T, S: fmt::Display>(line: usize, reason: S) -> Result<T, ParseError> {
+    Err(ParseError {
+        line,
+        reason: reason.to_string(),
+    })
+}
+
+type Line<'text> = (usize, &'text str);
+type Sections<'text> = (Vec<Line<'text>>, Vec<Line<'text>>, Vec<Line<'text>>);
+
+/* # pieces */
+
+fn number<T: FromStr>(line: usize, raw: &str) -> Result<T, ParseError> {
+    raw.trim()
+        .parse()
+        .or_else(|_err| error(line, format!("`{raw}` is not a number")))
+}
+
+fn boolean(line: usize, raw: &str) -> Result<bool, ParseError> {
+    raw.trim()
+        .parse()
+        .or_else(|_err| error(line, format!("`{raw}` is neither `true` nor `false`")))
+}
+
+/**
+a module is written as `0` or as `Z2xZ4`
+*/
+fn module(line: usize, raw: &str) -> Result<ModuleRecord, ParseError> {
+    match raw {
+        "0" => Ok(ModuleRecord::new()),
+        summands => summands
+            .split('x')
+            .map(|summand| {
+                summand.strip_prefix('Z').map_or_else(
+                    || error(line, format!("`{summand}` is not of the form `Zk`")),
+                    |coefficient| number(line, coefficient),
+                )
+            })
+            .collect(),
+    }
+}
+
+fn relation(
+    line: usize,
+    source: ModuleRecord,
+    target: ModuleRecord,
+    raw_bits: &str,
+) -> Result<RelationRecord, ParseError> {
+    let bits = raw_bits
+        .chars()
+        .map(|bit| match bit {
+            '0' => Ok(false),
+            '1' => Ok(true),
+            other => error(line, format!("`{other}` is not a bit")),
+        })
+        .collect::<Result<BitVec, _>>()?;
+    let expected_length = cardinality(&source)
+        .zip(cardinality(&target))
+        .and_then(|(nof_cols, nof_rows)| nof_cols.checked_mul(nof_rows));
+    if expected_length != Some(bits.len()) {
+        return error(
+            line,
+            format!(
+                "{} bits do not fit a relation between modules of orders {expected_length:?}",
+                bits.len()
+            ),
+        );
+    }
+    Ok(RelationRecord {
+        source,
+        target,
+        bits,
+    })
+}
+
+/**
+a relation written as `source-target-bits`, optionally followed by further `-`-separated fields
+*/
+fn relation_with_fields(
+    line: usize,
+    raw: &str,
+    nof_fields: usize,
+) -> Result<(RelationRecord, Vec<&str>), ParseError> {
+    let mut fields = raw.split('-');
+    match (fields.next(), fields.next(), fields.next()) {
+        (Some(source), Some(target), Some(bits)) => {
+            let rest: Vec<&str> = fields.collect();
+            if rest.len() != nof_fields {
+                return error(line, format!("unexpected number of fields in `{raw}`"));
+            }
+            Ok((
+                relation(line, module(line, source)?, module(line, target)?, bits)?,
+                rest,
+            ))
+        }
+        _ => error(line, format!("`{raw}` is not a relation")),
+    }
+}
+
+fn powers(line: usize, raw: &str) -> Result<(usize, usize), ParseError> {
+    match raw.split_once('-') {
+        Some((k, l)) => Ok((number(line, k)?, number(line, l)?)),
+        None => error(line, format!("`{raw}` is not a pair of powers")),
+    }
+}
+
+/* # sections */
+
+/**
+the results consist of the preamble, the classes and the trailer with the timings,
+separated by lines `===`. older results have no trailer.
+*/
+fn sections(text: &str) -> Result<Sections<'_>, ParseError> {
+    let mut sections = vec![Vec::new()];
+    for (line, content) in (1_usize..).zip(text.lines()) {
+        match content {
+            "===" => sections.push(Vec::new()),
+            _ => sections
+                .last_mut()
+                .expect("there is at least one section")
+                .push((line, content)),
+        }
+    }
+    let mut remaining = sections.into_iter();
+    match (
+        remaining.next(),
+        remaining.next(),
+        remaining.next(),
+        remaining.next(),
+    ) {
+        (Some(preamble), Some(body), trailer, None) => {
+            Ok((preamble, body, trailer.unwrap_or_default()))
+        }
+        _ => error(
+            text.lines().count(),
+            "expected a preamble, the classes and a trailer separated by `===`",
+        ),
+    }
+}
+
+fn preamble(lines: &[Line<'_>]) -> Result<Preamble, ParseError> {
+    let mut preamble = Preamble::default();
+    for &(line, content) in lines {
+        let Some((key, value)) = content.split_once(": ") else {
+            return error(line, format!("`{content}` is not of the form `key: value`"));
+        };
+        match key {
+            "Functor name" => value.clone_into(&mut preamble.functor),
+            "Object" => value.clone_into(&mut preamble.object),
+            "Morphism" => value.clone_into(&mut preamble.morphism),
+            "Endomorphism filter" => preamble.endomorphism_filter = Some(value.to_owned()),
+            "Number of classes" => preamble.number_of_classes = number(line, value)?,
+            "Number of endomorphisms" => {
+                preamble.number_of_endomorphisms = Some(number(line, value)?);
+            }
+            "Every class has a map" => preamble.every_class_has_a_map = Some(boolean(line, value)?),
+            "Every class has a bijection" => {
+                preamble.every_class_has_a_bijection = Some(boolean(line, value)?);
+            }
+            "Every class has exactly one bijection" => {
+                preamble.every_class_has_exactly_one_bijection = Some(boolean(line, value)?);
+            }
+            _ => return error(line, format!("unknown field `{key}`")),
+        }
+    }
+    Ok(preamble)
+}
+
+fn trailer(lines: &[Line<'_>]) -> Result<Trailer, ParseError> {
+    let mut trailer = Trailer::default();
+    for &(line, content) in lines {
+        if let Some(parameter) = content.strip_prefix("Parameter of the recursion: ") {
+            trailer.recursion = Some(number(line, parameter)?);
+        } else if let Some(duplicates) = content.strip_prefix("Duplicate objects removed: ") {
+            trailer.duplicate_objects_removed = Some(number(line, duplicates)?);
+        } else if let Some((stage, seconds)) = content.split_once(" after: ") {
+            trailer
+                .timings
+                .push((stage.to_owned(), number(line, seconds)?));
+        } else {
+            return error(
+                line,
+                format!("`{content}` is neither a timing nor a parameter"),
+            );
+        }
+    }
+    Ok(trailer)
+}
+
+fn classes(lines: &[Line<'_>]) -> Result<Vec<Vec<ObjectRecord>>, ParseError> {
+    let mut classes: Vec<Vec<ObjectRecord>> = Vec::new();
+    for &(line, content) in lines {
+        match content {
+            "---" => classes.push(Vec::new()),
+            // the name of the object follows
+            "-" => {}
+            _ => {
+                // newer results write the id of the class, which is its position
+                if let Some(id) = content.strip_prefix("--- ") {
+                    if number::<usize>(line, id)? != classes.len() {
+                        return error(line, format!("class {id} is out of order"));
+                    }
+                    classes.push(Vec::new());
+                    continue;
+                }
+                let Some(class) = classes.last_mut() else {
+                    return error(line, "an object outside of a class");
+                };
+                if let Some(object) = content.strip_suffix(':') {
+                    class.push(ObjectRecord {
+                        object: module(line, object)?,
+                        endomorphisms: Vec::new(),
+                    });
+                } else {
+                    let Some(object) = class.last_mut() else {
+                        return error(line, "an endomorphism outside of an object");
+                    };
+                    let endomorphism =
+                        relation(line, object.object.clone(), object.object.clone(), content)?;
+                    object.endomorphisms.push(endomorphism);
+                }
+            }
+        }
+    }
+    Ok(classes)
+}
+
+/**
+a pair starts with `left-cycle length--right-cycle length#`,
+followed by its isomorphisms `phi--psi---k-l--k'-l'`, one per line.
+*/
+fn full_classes(lines: &[Line<'_>]) -> Result<Vec<Vec<PairRecord>>, ParseError> {
+    let mut classes: Vec<Vec<PairRecord>> = Vec::new();
+    for &(line, content) in lines {
+        match content {
+            "---" => classes.push(Vec::new()),
+            // the pair follows
+            "--" => {}
+            _ => {
+                let Some(class) = classes.last_mut() else {
+                    return error(line, "a pair outside of a class");
+                };
+                if let Some(pair) = content.strip_suffix('#') {
+                    let Some((left, right)) = pair.split_once("--") else {
+                        return error(line, format!("`{pair}` is not a pair"));
+                    };
+                    let (left_relation, left_fields) = relation_with_fields(line, left, 1)?;
+                    let (right_relation, right_fields) = relation_with_fields(line, right, 1)?;
+                    class.push(PairRecord {
+                        left: left_relation,
+                        left_cycle_length: number(line, left_fields.concat().as_str())?,
+                        right: right_relation,
+                        right_cycle_length: number(line, right_fields.concat().as_str())?,
+                        isos: Vec::new(),
+                    });
+                } else {
+                    let Some(pair) = class.last_mut() else {
+                        return error(line, "an isomorphism outside of a pair");
+                    };
+                    let Some((morphisms, raw_powers)) = content.split_once("---") else {
+                        return error(line, format!("`{content}` is not an isomorphism"));
+                    };
+                    let (Some((phi, psi)), Some((left_powers, right_powers))) =
+                        (morphisms.split_once("--"), raw_powers.split_once("--"))
+                    else {
+                        return error(line, format!("`{content}` is not an isomorphism"));
+                    };
+                    pair.isos.push(IsoRecord {
+                        phi: relation_with_fields(line, phi, 0)?.0,
+                        psi: relation_with_fields(line, psi, 0)?.0,
+                        left_powers: powers(line, left_powers)?,
+                        right_powers: powers(line, right_powers)?,
+                    });
+                }
+            }
+        }
+    }
+    Ok(classes)
+}
+
+fn check_number_of_classes<T>(
+    preamble: &Preamble,
+    classes: &[T],
+    body: &[Line<'_>],
+) -> Result<(), ParseError> {
+    match classes.len() == preamble.number_of_classes {
+        true => Ok(()),
+        false => error(
+            body.last().map_or(0, |&(line, _)| line),
+            format!(
+                "found {} classes out of {}, the file might be truncated",
+                classes.len(),
+                preamble.number_of_classes
+            ),
+        ),
+    }
+}
+
+/* # results */
+
+/**
+reads the output of the display of `IsoClasses`
+*/
+pub fn parse_classes(text: &str) -> Result<ClassesRecord, ParseError> {
+    let (preamble_lines, body, trailer_lines) = sections(text)?;
+    let record = ClassesRecord {
+        preamble: preamble(&preamble_lines)?,
+        classes: classes(&body)?,
+        trailer: trailer(&trailer_lines)?,
+    };
+    check_number_of_classes(&record.preamble, &record.classes, &body)?;
+    Ok(record)
+}
+
+/**
+reads the output of the display of `IsoClassesFull`
+*/
+pub fn parse_full_classes(text: &str) -> Result<FullClassesRecord, ParseError> {
+    let (preamble_lines, body, trailer_lines) = sections(text)?;
+    let record = FullClassesRecord {
+        preamble: preamble(&preamble_lines)?,
+        classes: full_classes(&body)?,
+        trailer: trailer(&trailer_lines)?,
+    };
+    check_number_of_classes(&record.preamble, &record.classes, &body)?;
+    Ok(record)
+}
+
+/**
+only the classes without the isomorphisms count their endomorphisms in the preamble,
+which tells the two kinds of results apart
+*/
+pub fn parse(text: &str) -> Result<Record, ParseError> {
+    match text
+        .lines()
+        .take_while(|&line| line != "===")
+        .any(|line| line.starts_with("Number of endomorphisms: "))
+    {
+        true => parse_classes(text).map(Record::Classes),
+        false => parse_full_classes(text).map(Record::FullClasses),
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        category::{
+            functors::szymczak::{SzymczakClasses, SzymczakClassesFull},
+            relation::Relation,
+            Category,
+        },
+        ralg::{
+            cgroup::{ideal::CIdeal, C},
+            module::canon::object::Object as Module,
+        },
+        util::json::ToJson,
+    };
+    use typenum::U4 as N;
+
+    type R = C<N>;
+    type I = CIdeal<N>;
+
+    #[test]
+    fn reading_classes() {
+        let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
+        let classes = SzymczakClasses::<Module<R, I>, Relation<R, I>>::functor(&category, 8);
+        let text = format!(
+            "{classes}===\nCategory generated after: 0.25\nParameter of the recursion: 8\nDuplicate objects removed: 0\n"
+        );
+
+        let record = parse_classes(&text).expect("the text was just written");
+        assert_eq!(record.to_json(), classes.to_json());
+        assert_eq!(record.preamble.endomorphism_filter.as_deref(), Some("wide"));
+        assert_eq!(
+            record.trailer,
+            Trailer {
+                timings: vec![("Category generated".to_owned(), 0.25_f64)],
+                recursion: Some(8),
+                duplicate_objects_removed: Some(0),
+            }
+        );
+        assert!(matches!(parse(&text), Ok(Record::Classes(_))));
+    }
+
+    #[test]
+    fn reading_full_classes() {
+        let category = Category::<Module<R, I>, Relation<R, I>>::new(1);
+        let classes = SzymczakClassesFull::<Module<R, I>, Relation<R, I>>::all_isos(
+            SzymczakClasses::<Module<R, I>, Relation<R, I>>::functor(&category, 8),
+            &category,
+        );
+        let text = format!("{classes}");
+
+        let record = parse_full_classes(&text).expect("the text was just written");
+        assert_eq!(record.to_json(), classes.to_json());
+        assert_eq!(record.trailer, Trailer::default(), "there was no trailer");
+        assert!(matches!(parse(&text), Ok(Record::FullClasses(_))));
+    }
+
+    #[test]
+    fn rejecting_malformed_results() {
+        let preamble = "Functor name: Szymczak\nObject: Zn-Module\nMorphism: Relation\nNumber of endomorphisms: 1\nNumber of classes: 1\n===\n";
+
+        assert!(parse_classes(&format!("{preamble}---\n-\nZ2:\n1001\n")).is_ok());
+        assert!(parse_classes(&format!("{preamble}--- 0\n-\nZ2:\n1001\n")).is_ok());
+        assert_eq!(
+            parse_classes(&format!("{preamble}--- 1\n-\nZ2:\n1001\n")).map_err(|error| error.line),
+            Err(7),
+            "the id of a class is its position"
+        );
+        assert_eq!(
+            parse_classes(&format!("{preamble}---\n-\nZ2:\n101\n")).map_err(|error| error.line),
+            Err(10),
+            "wrong number of bits"
+        );
+        assert_eq!(
+            parse_classes(&format!("{preamble}---\n-\nZ2:\n1002\n")).map_err(|error| error.line),
+            Err(10),
+            "not a bit"
+        );
+        assert_eq!(
+            parse_classes(&format!("{preamble}---\n-\nZ2:\n1001\n---\n"))
+                .map_err(|error| error.line),
+            Err(11),
+            "more classes than declared"
+        );
+        assert_eq!(
+            parse_classes("Functor: Szymczak\n===\n").map_err(|error| error.line),
+            Err(1),
+            "unknown field"
+        );
+    }
+}
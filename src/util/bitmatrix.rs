@@ -1 +1,345 @@
-// use bitvec::prelude::*;
+use bitvec::prelude::*;
+use std::{fmt, hash, mem};
+
+const WORD: usize = mem::size_of::<usize>().saturating_mul(8);
+
+/**
+a boolean matrix indexed like `VecD2`, with one bit per entry.
+every row is padded to whole words, so rows are combined a word at a time,
+and the padding is kept zero, so the words can be compared and hashed directly
+*/
+#[derive(Clone)]
+pub struct BitMatrix {
+    pub nof_cols: usize,
+    pub nof_rows: usize,
+    bits: BitVec<usize, Lsb0>,
+}
+
+/* ## debug */
+
+impl fmt::Debug for BitMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "B2({:?}x{:?}){}",
+            self.nof_cols,
+            self.nof_rows,
+            self.iter()
+                .map(|entry| match entry {
+                    true => '1',
+                    false => '0',
+                })
+                .collect::<String>()
+        )
+    }
+}
+
+/* ## equality and hashing */
+
+impl PartialEq for BitMatrix {
+    fn eq(&self, other: &Self) -> bool {
+        self.nof_cols == other.nof_cols
+            && self.nof_rows == other.nof_rows
+            && self.bits.as_raw_slice() == other.bits.as_raw_slice()
+    }
+}
+
+impl Eq for BitMatrix {}
+
+impl hash::Hash for BitMatrix {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.nof_cols.hash(state);
+        self.nof_rows.hash(state);
+        self.bits.as_raw_slice().hash(state);
+    }
+}
+
+impl BitMatrix {
+    /* # constructors */
+
+    pub fn zeros(nof_cols: usize, nof_rows: usize) -> Self {
+        Self {
+            nof_cols,
+            nof_rows,
+            bits: bitvec![usize, Lsb0; 0; nof_cols.div_ceil(WORD).saturating_mul(WORD).saturating_mul(nof_rows)],
+        }
+    }
+
+    /**
+    the entries are given row after row, as in the buffer of `VecD2`
+    */
+    pub fn from_buffer<I>(buffer: I, nof_cols: usize, nof_rows: usize) -> Self
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let mut matrix = Self::zeros(nof_cols, nof_rows);
+        for (entry, (row, col)) in buffer
+            .into_iter()
+            .zip((0..nof_rows).flat_map(|row| (0..nof_cols).map(move |col| (row, col))))
+        {
+            matrix.set(col, row, entry);
+        }
+        matrix
+    }
+
+    /* # getters */
+
+    const fn words_per_row(&self) -> usize {
+        self.nof_cols.div_ceil(WORD)
+    }
+
+    fn row_bits(&self, row: usize) -> &BitSlice<usize, Lsb0> {
+        let start = row
+            .saturating_mul(self.words_per_row())
+            .saturating_mul(WORD);
+        self.bits
+            .get(start..start.saturating_add(self.nof_cols))
+            .expect("row is in proper bounds")
+    }
+
+    fn row_words(&self, row: usize) -> &[usize] {
+        let words_per_row = self.words_per_row();
+        let start = row.saturating_mul(words_per_row);
+        self.bits
+            .as_raw_slice()
+            .get(start..start.saturating_add(words_per_row))
+            .expect("row is in proper bounds")
+    }
+
+    fn row_words_mut(&mut self, row: usize) -> &mut [usize] {
+        let words_per_row = self.words_per_row();
+        let start = row.saturating_mul(words_per_row);
+        self.bits
+            .as_raw_mut_slice()
+            .get_mut(start..start.saturating_add(words_per_row))
+            .expect("row is in proper bounds")
+    }
+
+    fn index(&self, col: usize, row: usize) -> Option<usize> {
+        (col < self.nof_cols && row < self.nof_rows).then(|| {
+            row.saturating_mul(self.words_per_row())
+                .saturating_mul(WORD)
+                .saturating_add(col)
+        })
+    }
+
+    pub fn get(&self, col: usize, row: usize) -> Option<bool> {
+        self.index(col, row)
+            .and_then(|index| self.bits.get(index).as_deref().copied())
+    }
+
+    pub fn set(&mut self, col: usize, row: usize, value: bool) {
+        if let Some(index) = self.index(col, row) {
+            self.bits.set(index, value);
+        }
+    }
+
+    /**
+    the entries row after row, as in the buffer of `VecD2`
+    */
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.nof_rows).flat_map(|row| self.row_bits(row).iter().by_vals())
+    }
+
+    pub fn buffer(&self) -> Vec<bool> {
+        self.iter().collect()
+    }
+
+    /* # counting */
+
+    pub fn row_counts(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nof_rows).map(|row| {
+            self.row_words(row)
+                .iter()
+                .map(|word| usize::try_from(word.count_ones()).expect("usize has enough bits"))
+                .sum()
+        })
+    }
+
+    /**
+    the columns with at least one and the ones with at least two entries set, as row words.
+    a column is seen again if it is set in a row after being seen in an earlier one
+    */
+    fn col_occurrences(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut seen = vec![0; self.words_per_row()];
+        let mut twice = vec![0; self.words_per_row()];
+        for row in 0..self.nof_rows {
+            for ((seen_word, twice_word), word) in seen
+                .iter_mut()
+                .zip(twice.iter_mut())
+                .zip(self.row_words(row))
+            {
+                *twice_word |= *seen_word & *word;
+                *seen_word |= *word;
+            }
+        }
+        (seen, twice)
+    }
+
+    /**
+    the padding is zero, so every column is hit exactly when all of them are counted
+    */
+    pub fn every_col_is_hit(&self) -> bool {
+        self.col_occurrences()
+            .0
+            .iter()
+            .map(|word| usize::try_from(word.count_ones()).expect("usize has enough bits"))
+            .sum::<usize>()
+            == self.nof_cols
+    }
+
+    pub fn no_col_is_hit_twice(&self) -> bool {
+        self.col_occurrences().1.iter().all(|word| *word == 0)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.row_counts().sum()
+    }
+
+    /* # transformations */
+
+    pub fn transpose(&self) -> Self {
+        let mut transposed = Self::zeros(self.nof_rows, self.nof_cols);
+        for row in 0..self.nof_rows {
+            for col in self.row_bits(row).iter_ones() {
+                transposed.set(row, col, true);
+            }
+        }
+        transposed
+    }
+
+    /**
+    the relational composite, self first, as in `Relation::compose`.
+    the row of the composite is the union of the rows of self picked by the row of other,
+    this assumes that `self.nof_rows == other.nof_cols`
+    */
+    pub fn compose(&self, other: &Self) -> Self {
+        let mut composite = Self::zeros(self.nof_cols, other.nof_rows);
+        for row in 0..other.nof_rows {
+            for middle in other.row_bits(row).iter_ones() {
+                for (word, self_word) in composite
+                    .row_words_mut(row)
+                    .iter_mut()
+                    .zip(self.row_words(middle))
+                {
+                    *word |= *self_word;
+                }
+            }
+        }
+        composite
+    }
+
+    /**
+    the entrywise conjunction, this assumes that the dimensions are the same
+    */
+    pub fn and(&self, other: &Self) -> Self {
+        let mut conjunction = self.clone();
+        for (word, other_word) in conjunction
+            .bits
+            .as_raw_mut_slice()
+            .iter_mut()
+            .zip(other.bits.as_raw_slice())
+        {
+            *word &= *other_word;
+        }
+        conjunction
+    }
+
+    pub fn is_contained_in(&self, other: &Self) -> bool {
+        self.nof_cols == other.nof_cols
+            && self.nof_rows == other.nof_rows
+            && self
+                .bits
+                .as_raw_slice()
+                .iter()
+                .zip(other.bits.as_raw_slice())
+                .all(|(word, other_word)| word & !other_word == 0)
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ralg::matrix::VecD2;
+
+    // wider than a word, so that the rows are padded
+    fn matrices() -> Vec<(Vec<bool>, usize, usize)> {
+        let wide = WORD.saturating_add(3);
+        vec![
+            (vec![true, false, true, true, false, false], 3, 2),
+            (vec![false, true, true, false, true, true], 2, 3),
+            (
+                vec![false, true, false, false, false, true, true, false, false],
+                3,
+                3,
+            ),
+            (
+                (0..wide.saturating_mul(2))
+                    .map(|index| index % 3 == 0)
+                    .collect(),
+                wide,
+                2,
+            ),
+            (
+                (0..wide.saturating_mul(2))
+                    .map(|index| index % 5 == 1)
+                    .collect(),
+                2,
+                wide,
+            ),
+        ]
+    }
+
+    #[test]
+    fn indexing_agrees_with_vecd2() {
+        for (buffer, nof_cols, nof_rows) in matrices() {
+            let bits = BitMatrix::from_buffer(buffer.clone(), nof_cols, nof_rows);
+            let vec = VecD2::from_buffer(buffer.clone(), nof_cols, nof_rows);
+            assert_eq!(bits.buffer(), buffer);
+            for col in 0..nof_cols {
+                for row in 0..nof_rows {
+                    assert_eq!(bits.get(col, row).as_ref(), vec.get(col, row));
+                }
+            }
+            assert_eq!(bits.get(nof_cols, 0), None);
+            assert_eq!(bits.transpose().buffer(), vec.clone().transpose().buffer());
+            let col_counts: Vec<usize> = vec
+                .cols()
+                .map(|col| col.filter(|entry| **entry).count())
+                .collect();
+            assert_eq!(
+                bits.every_col_is_hit(),
+                col_counts.iter().all(|count| *count > 0)
+            );
+            assert_eq!(
+                bits.no_col_is_hit_twice(),
+                col_counts.iter().all(|count| *count <= 1)
+            );
+        }
+    }
+
+    #[test]
+    fn composition_agrees_with_vecd2() {
+        for (left_buffer, left_cols, left_rows) in matrices() {
+            for (right_buffer, right_cols, right_rows) in matrices() {
+                if left_rows != right_cols {
+                    continue;
+                }
+                let bits =
+                    BitMatrix::from_buffer(left_buffer.clone(), left_cols, left_rows).compose(
+                        &BitMatrix::from_buffer(right_buffer.clone(), right_cols, right_rows),
+                    );
+                let vec = VecD2::from_buffer(left_buffer.clone(), left_cols, left_rows)
+                    .compose_unchecked_bool(&VecD2::from_buffer(
+                        right_buffer,
+                        right_cols,
+                        right_rows,
+                    ));
+                assert_eq!(bits.buffer(), vec.buffer());
+                assert_eq!((bits.nof_cols, bits.nof_rows), (vec.nof_cols, vec.nof_rows));
+            }
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use bitvec::vec::BitVec;
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/* # compact encoding */
+
+/**
+a binary encoding of the state of a long computation.
+numbers are little endian and every collection is prefixed with its length,
+so the decoding fails on a truncated buffer instead of reading garbage.
+*/
+pub trait Persistent: Sized {
+    fn persist(&self, bytes: &mut Vec<u8>);
+    fn restore(bytes: &mut &[u8]) -> Option<Self>;
+}
+
+fn take<'bytes>(bytes: &mut &'bytes [u8], length: usize) -> Option<&'bytes [u8]> {
+    let (taken, remaining) = (bytes.len() >= length).then(|| bytes.split_at(length))?;
+    *bytes = remaining;
+    Some(taken)
+}
+
+impl Persistent for u16 {
+    fn persist(&self, bytes: &mut Vec<u8>) {
+        bytes.extend(self.to_le_bytes());
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Self> {
+        Some(Self::from_le_bytes(take(bytes, 2)?.try_into().ok()?))
+    }
+}
+
+impl Persistent for u64 {
+    fn persist(&self, bytes: &mut Vec<u8>) {
+        bytes.extend(self.to_le_bytes());
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Self> {
+        Some(Self::from_le_bytes(take(bytes, 8)?.try_into().ok()?))
+    }
+}
+
+// the checkpoints are meant to be moved between machines, so the width is fixed
+impl Persistent for usize {
+    fn persist(&self, bytes: &mut Vec<u8>) {
+        u64::try_from(*self)
+            .expect("sizes fit in 64 bits")
+            .persist(bytes);
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Self> {
+        Self::try_from(u64::restore(bytes)?).ok()
+    }
+}
+
+impl Persistent for String {
+    fn persist(&self, bytes: &mut Vec<u8>) {
+        self.len().persist(bytes);
+        bytes.extend(self.as_bytes());
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Self> {
+        let length = usize::restore(bytes)?;
+        Self::from_utf8(take(bytes, length)?.to_vec()).ok()
+    }
+}
+
+/**
+bits are packed, eight in a byte
+*/
+impl Persistent for BitVec<u8> {
+    fn persist(&self, bytes: &mut Vec<u8>) {
+        self.len().persist(bytes);
+        bytes.extend(self.as_raw_slice());
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Self> {
+        let length = usize::restore(bytes)?;
+        let mut bits = Self::from_slice(take(bytes, length.div_ceil(8))?);
+        bits.truncate(length);
+        Some(bits)
+    }
+}
+
+impl<T: Persistent> Persistent for Vec<T> {
+    fn persist(&self, bytes: &mut Vec<u8>) {
+        self.len().persist(bytes);
+        for item in self {
+            item.persist(bytes);
+        }
+    }
+
+    fn restore(bytes: &mut &[u8]) -> Option<Self> {
+        let length = usize::restore(bytes)?;
+        // the length is not trusted with the allocation, a corrupted one would abort
+        let mut items = Self::new();
+        for _ in 0..length {
+            items.push(T::restore(bytes)?);
+        }
+        Some(items)
+    }
+}
+
+/* # checkpoint files */
+
+const MAGIC: &[u8] = b"szymczak-leray checkpoint\n";
+const VERSION: u16 = 1;
+
+fn corrupted<T: Into<Box<dyn std::error::Error + Send + Sync>>>(reason: T) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+/**
+a file holding the state of a computation, rewritten at most once per `interval`.
+the tag names the computation, e.g. the ring and the dimension,
+so a checkpoint is never resumed by a different one.
+without `resume` the existing checkpoint is ignored and eventually overwritten.
+*/
+pub struct Checkpoint {
+    pub path: PathBuf,
+    pub tag: String,
+    pub interval: Duration,
+    pub resume: bool,
+    last_saved: Mutex<Instant>,
+}
+
+impl Checkpoint {
+    pub fn new(path: PathBuf, tag: String, interval: Duration, resume: bool) -> Self {
+        Self {
+            path,
+            tag,
+            interval,
+            resume,
+            last_saved: Mutex::new(Instant::now()),
+        }
+    }
+
+    /**
+    `None` if there is nothing to resume from
+    */
+    pub fn load<T, F: FnOnce(&mut &[u8]) -> Option<T>>(&self, restore: F) -> io::Result<Option<T>> {
+        if !self.resume || !self.path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read(&self.path)?;
+        let mut bytes = contents.as_slice();
+
+        if take(&mut bytes, MAGIC.len()) != Some(MAGIC) || u16::restore(&mut bytes) != Some(VERSION)
+        {
+            return Err(corrupted(format!(
+                "{} is not a checkpoint of this version",
+                self.path.display()
+            )));
+        }
+        match String::restore(&mut bytes) {
+            Some(ref tag) if *tag == self.tag => {}
+            Some(tag) => {
+                return Err(corrupted(format!(
+                    "{} is a checkpoint of {tag}, not of {}",
+                    self.path.display(),
+                    self.tag
+                )))
+            }
+            None => return Err(corrupted(format!("{} is truncated", self.path.display()))),
+        }
+        match restore(&mut bytes) {
+            Some(state) if bytes.is_empty() => Ok(Some(state)),
+            _ => Err(corrupted(format!("{} is corrupted", self.path.display()))),
+        }
+    }
+
+    fn write<F: FnOnce(&mut Vec<u8>)>(&self, persist: F) -> io::Result<()> {
+        let mut bytes = MAGIC.to_vec();
+        VERSION.persist(&mut bytes);
+        self.tag.persist(&mut bytes);
+        persist(&mut bytes);
+
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        // as with the results, an existing checkpoint is always a complete one
+        let mut partial = self.path.clone().into_os_string();
+        partial.push(".part");
+        fs::write(&partial, bytes)?;
+        fs::rename(partial, &self.path)
+    }
+
+    pub fn save<F: FnOnce(&mut Vec<u8>)>(&self, persist: F) -> io::Result<()> {
+        self.write(persist)?;
+        *self
+            .last_saved
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Instant::now();
+        Ok(())
+    }
+
+    /**
+    skipped if another thread is saving at the moment.
+    a failure is reported, but it does not stop the computation,
+    which is usually worth more than its checkpoint.
+    */
+    #[allow(
+        clippy::print_stderr,
+        reason = "the failure should not be mixed with the results"
+    )]
+    pub fn save_if_due<F: FnOnce(&mut Vec<u8>)>(&self, persist: F) {
+        let Ok(mut last_saved) = self.last_saved.try_lock() else {
+            return;
+        };
+        if last_saved.elapsed() < self.interval {
+            return;
+        }
+        if let Err(error) = self.write(persist) {
+            eprintln!("checkpoint {} not saved: {error}", self.path.display());
+        }
+        *last_saved = Instant::now();
+    }
+
+    pub fn remove(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encoding_round_trip() {
+        let state: (Vec<u16>, String, BitVec<u8>) = (
+            vec![2, 4, 62],
+            "Z2xZ4".to_owned(),
+            [true, false, true, true, false, false, true, false, true]
+                .into_iter()
+                .collect(),
+        );
+        let mut bytes = Vec::new();
+        state.0.persist(&mut bytes);
+        state.1.persist(&mut bytes);
+        state.2.persist(&mut bytes);
+
+        let mut remaining = bytes.as_slice();
+        assert_eq!(Vec::<u16>::restore(&mut remaining), Some(state.0));
+        assert_eq!(String::restore(&mut remaining), Some(state.1));
+        assert_eq!(BitVec::<u8>::restore(&mut remaining), Some(state.2));
+        assert!(remaining.is_empty(), "everything is read back");
+
+        let mut truncated = bytes.get(..bytes.len().saturating_sub(1)).unwrap_or(&[]);
+        assert_eq!(Vec::<u16>::restore(&mut truncated), Some(vec![2, 4, 62]));
+        assert_eq!(String::restore(&mut truncated), Some("Z2xZ4".to_owned()));
+        assert_eq!(BitVec::<u8>::restore(&mut truncated), None);
+    }
+
+    #[test]
+    fn saving_and_resuming() {
+        let path =
+            std::env::temp_dir().join(format!("szymczak-checkpoint-{}/state", std::process::id()));
+        let checkpoint = |tag: &str, resume: bool| {
+            Checkpoint::new(path.clone(), tag.to_owned(), Duration::ZERO, resume)
+        };
+
+        checkpoint("Z4 dim 1", false)
+            .save(|bytes| vec![1_u16, 2_u16].persist(bytes))
+            .expect("temporary directory is writable");
+        assert_eq!(
+            checkpoint("Z4 dim 1", false)
+                .load(Vec::<u16>::restore)
+                .expect("checkpoint is readable"),
+            None,
+            "nothing is resumed unless asked to"
+        );
+        assert_eq!(
+            checkpoint("Z4 dim 1", true)
+                .load(Vec::<u16>::restore)
+                .expect("checkpoint is readable"),
+            Some(vec![1, 2])
+        );
+        assert!(
+            checkpoint("Z6 dim 1", true)
+                .load(Vec::<u16>::restore)
+                .is_err(),
+            "checkpoint of another computation"
+        );
+        assert!(
+            checkpoint("Z4 dim 1", true).load(u16::restore).is_err(),
+            "trailing bytes"
+        );
+
+        checkpoint("Z4 dim 1", true)
+            .remove()
+            .expect("checkpoint is removable");
+        assert!(!path.exists());
+        if let Some(directory) = path.parent() {
+            fs::remove_dir_all(directory).expect("temporary directory is removable");
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt;
+
+/* # json values */
+
+/**
+a minimal json document, enough to write down the results.
+the keys of an object keep the order in which they were given,
+so the output is stable between runs.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(u64),
+    Float(f64),
+    String(String),
+    Array(Vec<Self>),
+    Object(Vec<(String, Self)>),
+}
+
+impl Json {
+    pub fn object<const N: usize>(entries: [(&str, Self); N]) -> Self {
+        Self::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value))
+                .collect(),
+        )
+    }
+}
+
+/* ## serialization */
+
+fn write_string(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for character in string.chars() {
+        match character {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            '\r' => write!(f, "\\r")?,
+            control if control.is_control() => write!(f, "\\u{:04x}", u32::from(control))?,
+            other => write!(f, "{other}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Number(value) => write!(f, "{value}"),
+            // json has no representation of infinities
+            Self::Float(value) if !value.is_finite() => write!(f, "null"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::String(ref value) => write_string(f, value),
+            Self::Array(ref values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(ref entries) => {
+                write!(f, "{{")?;
+                for (index, entry) in entries.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, &entry.0)?;
+                    write!(f, ":{}", entry.1)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/* # conversion */
+
+/**
+the json produced for the results has the following schema.
+
+a module is the array of its torsion coefficients, `Z2xZ4` is `[2,4]`, `0` is `[]`.
+
+a relation is
+`{"source": module, "target": module, "matrix": [string]}`,
+with one string per element of the source, in the order of their indices.
+the k-th character of a string is `1` if the element is related
+to the k-th element of the target and `0` otherwise.
+an index of an element is written in the mixed radix of the torsion coefficients,
+with the first coordinate being the least significant.
+
+a linear map is
+`{"source": module, "target": module, "matrix": [[number]]}`,
+the matrix given by its rows.
+
+iso classes are
+```text
+{
+  "functor": string, "object": string, "morphism": string, "endomorphism_filter": string,
+  "number_of_endomorphisms": number, "number_of_classes": number,
+  "every_class_has_a_map": bool, "every_class_has_a_bijection": bool,
+  "every_class_has_exactly_one_bijection": bool,
+  "classes": [{"id": number, "objects": [{"object": module, "endomorphisms": [relation]}]}]
+}
+```
+where the id of a class is its position, which is the same in every run for the relations.
+
+iso classes with all the isomorphisms are
+```text
+{
+  "functor": string, "object": string, "morphism": string, "endomorphism_filter": string,
+  "number_of_classes": number,
+  "classes": [[pair]]
+}
+```
+where for the szymczak functor a pair is
+```text
+{
+  "left": relation, "left_cycle_length": number,
+  "right": relation, "right_cycle_length": number,
+  "isos": [{"phi": relation, "psi": relation, "left_powers": [k, l], "right_powers": [k', l']}]
+}
+```
+such that `phi` goes from left to right, `psi` from right to left and
+`psi phi left^k = left^l`, `phi psi right^k' = right^l'`.
+
+the filter names the endomorphisms which were classified,
+it is `null` in the results converted from the ones written before it was recorded.
+
+the command line wraps the result as
+```text
+{
+  "result": ..., "timings": {stage: seconds},
+  "recursion": number, "duplicate_objects_removed": number
+}
+```
+where the last two are present only for the stages they apply to,
+the latter when the category is built as a skeleton.
+*/
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Json {
+        Json::Bool(*self)
+    }
+}
+
+impl ToJson for u16 {
+    fn to_json(&self) -> Json {
+        Json::Number(u64::from(*self))
+    }
+}
+
+impl ToJson for usize {
+    fn to_json(&self) -> Json {
+        u64::try_from(*self).map_or(Json::Null, Json::Number)
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Json {
+        Json::String(self.to_owned())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json {
+        self.as_str().to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Json {
+        self.as_slice().to_json()
+    }
+}
+
+impl<A: ToJson, B: ToJson> ToJson for (A, B) {
+    fn to_json(&self) -> Json {
+        Json::Array(vec![self.0.to_json(), self.1.to_json()])
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn serialization() {
+        let json = Json::object([
+            ("name", "Z\"2\"\n".to_json()),
+            ("numbers", vec![1_u16, 2_u16].to_json()),
+            ("pair", (3_usize, true).to_json()),
+            ("time", Json::Float(0.5)),
+            ("empty", Json::Object(Vec::new())),
+            ("nothing", Json::Null),
+        ]);
+        assert_eq!(
+            json.to_string(),
+            r#"{"name":"Z\"2\"\n","numbers":[1,2],"pair":[3,true],"time":0.5,"empty":{},"nothing":null}"#
+        );
+    }
+}
@@ -1,2 +1,5 @@
 pub mod bitmatrix;
 pub mod category_of_relations;
+pub mod checkpoint;
+pub mod json;
+pub mod union_find;
@@ -0,0 +1,124 @@
+/**
+disjoint sets of the numbers `0..len`, joined by `union`.
+the trees are kept shallow by joining the lower one under the higher one
+and by pointing every visited node at its grandparent while looking for the root
+*/
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    fn parent(&self, node: usize) -> usize {
+        *self.parents.get(node).expect("node is in proper bounds")
+    }
+
+    fn rank(&self, node: usize) -> u8 {
+        *self.ranks.get(node).expect("node is in proper bounds")
+    }
+
+    pub fn find(&mut self, node: usize) -> usize {
+        let mut current = node;
+        while self.parent(current) != current {
+            let grandparent = self.parent(self.parent(current));
+            *self
+                .parents
+                .get_mut(current)
+                .expect("node is in proper bounds") = grandparent;
+            current = grandparent;
+        }
+        current
+    }
+
+    /**
+    `false` if the two were already in the same set
+    */
+    pub fn union(&mut self, left: usize, right: usize) -> bool {
+        let (left_root, right_root) = (self.find(left), self.find(right));
+        if left_root == right_root {
+            return false;
+        }
+        let (lower, higher) = match self.rank(left_root) < self.rank(right_root) {
+            true => (left_root, right_root),
+            false => (right_root, left_root),
+        };
+        *self
+            .parents
+            .get_mut(lower)
+            .expect("node is in proper bounds") = higher;
+        if self.rank(lower) == self.rank(higher) {
+            let rank = self
+                .ranks
+                .get_mut(higher)
+                .expect("node is in proper bounds");
+            *rank = rank.saturating_add(1);
+        }
+        true
+    }
+
+    pub fn are_joined(&mut self, left: usize, right: usize) -> bool {
+        self.find(left) == self.find(right)
+    }
+
+    /**
+    the sets ordered by their least elements, each of them in the increasing order
+    */
+    pub fn into_sets(mut self) -> Vec<Vec<usize>> {
+        let mut positions = vec![None; self.len()];
+        let mut sets: Vec<Vec<usize>> = Vec::new();
+        for node in 0..self.len() {
+            let root = self.find(node);
+            let position = positions
+                .get_mut(root)
+                .expect("node is in proper bounds")
+                .get_or_insert_with(|| {
+                    sets.push(Vec::new());
+                    sets.len().saturating_sub(1)
+                });
+            sets.get_mut(*position)
+                .expect("position of an existing set")
+                .push(node);
+        }
+        sets
+    }
+}
+
+// - - -
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn joining_sets() {
+        let mut sets = UnionFind::new(7);
+        assert!(sets.union(1, 4));
+        assert!(sets.union(5, 4));
+        assert!(sets.union(6, 2));
+        assert!(!sets.union(1, 5), "already joined through 4");
+        assert!(sets.are_joined(5, 1));
+        assert!(!sets.are_joined(0, 2));
+
+        assert_eq!(
+            sets.into_sets(),
+            vec![vec![0], vec![1, 4, 5], vec![2, 6], vec![3]]
+        );
+        assert!(UnionFind::new(0).into_sets().is_empty());
+    }
+}